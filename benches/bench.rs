@@ -60,6 +60,19 @@ fn example_items() -> Vec<TreeItem<&'static str>> {
     ]
 }
 
+#[must_use]
+fn many_items() -> Vec<TreeItem<String>> {
+    (0..100)
+        .map(|parent| {
+            let children = (0..100)
+                .map(|child| TreeItem::new_leaf(format!("Child {child}")))
+                .collect();
+            TreeItem::new(format!("Parent {parent}"), children)
+                .expect("all item identifiers are unique")
+        })
+        .collect()
+}
+
 fn init(criterion: &mut Criterion) {
     let mut group = criterion.benchmark_group("init");
     group.throughput(Throughput::Elements(1)); // Frames per second
@@ -115,6 +128,22 @@ fn renders(criterion: &mut Criterion) {
         );
     });
 
+    group.bench_function("many-items", |bencher| {
+        let items = many_items();
+        let tree = Tree::new(&items).unwrap();
+        let mut state = TreeState::default();
+        for item in &items {
+            state.open(vec![item.identifier()]);
+        }
+        bencher.iter_batched(
+            || (tree.clone(), Buffer::empty(buffer_size)),
+            |(tree, mut buffer)| {
+                black_box(tree).render(buffer_size, black_box(&mut buffer), black_box(&mut state));
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
     group.finish();
 }
 
@@ -1,6 +1,6 @@
 use std::time::{Duration, Instant};
 
-use managarr_tree_widget::{Tree, TreeItem, TreeState};
+use managarr_tree_widget::{ToggleHit, Tree, TreeItem, TreeState};
 use ratatui::backend::{Backend, CrosstermBackend};
 use ratatui::crossterm::event::{Event, KeyCode, KeyModifiers, MouseEventKind};
 use ratatui::layout::{Position, Rect};
@@ -155,15 +155,23 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> std::io::Res
                     KeyCode::Esc => app.state.select(Vec::new()),
                     KeyCode::Home => app.state.select_first(),
                     KeyCode::End => app.state.select_last(),
-                    KeyCode::PageDown => app.state.scroll_down(3),
-                    KeyCode::PageUp => app.state.scroll_up(3),
+                    KeyCode::PageDown => app
+                        .state
+                        .select_page_down(app.state.viewport_height().into()),
+                    KeyCode::PageUp => app.state.select_page_up(app.state.viewport_height().into()),
                     _ => false,
                 },
                 Event::Mouse(mouse) => match mouse.kind {
                     MouseEventKind::ScrollDown => app.state.scroll_down(1),
                     MouseEventKind::ScrollUp => app.state.scroll_up(1),
                     MouseEventKind::Down(_button) => {
-                        app.state.click_at(Position::new(mouse.column, mouse.row))
+                        let position = Position::new(mouse.column, mouse.row);
+                        if app.state.toggle_target_at(position) == Some(ToggleHit::Symbol) {
+                            let identifier = app.state.rendered_at(position).unwrap_or_default();
+                            app.state.toggle(identifier.to_vec())
+                        } else {
+                            app.state.click_at(position)
+                        }
                     }
                     _ => false,
                 },
@@ -0,0 +1,45 @@
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use ratatui::style::Style;
+use ratatui::text::Span;
+
+use crate::tree_item::TreeItem;
+
+/// A closure stored in the [`Tree`](crate::Tree).
+///
+/// Shared so the [`Tree`](crate::Tree) stays cheap to clone and printed as a placeholder as closures can not be debug printed.
+pub struct Callback<F: ?Sized>(pub Rc<F>);
+
+/// Decides whether a [`TreeItem`] is shown, see [`Tree::filter`](crate::Tree::filter).
+pub type ItemFilter<'a, T> = Callback<dyn Fn(&TreeItem<T>) -> bool + 'a>;
+
+/// Decoration drawn between the node symbol and the label, see [`Tree::prefix`](crate::Tree::prefix).
+pub type ItemPrefix<'a, T> = Callback<dyn Fn(&TreeItem<T>, usize) -> Span<'a> + 'a>;
+
+/// Formats the amount of items into the block title, see [`Tree::block_with_count`](crate::Tree::block_with_count).
+pub type CountFormat<'a> = Callback<dyn Fn(usize) -> String + 'a>;
+
+/// Base style of the labels on a depth, see [`Tree::label_base_style_fn`](crate::Tree::label_base_style_fn).
+pub type DepthStyle<'a> = Callback<dyn Fn(usize) -> Style + 'a>;
+
+impl<F: ?Sized> Clone for Callback<F> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for Callback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Callback(..)")
+    }
+}
+
+impl<F: ?Sized> Deref for Callback<F> {
+    type Target = F;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
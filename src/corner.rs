@@ -0,0 +1,34 @@
+/// Corner of the area in which the first item is rendered, see [`Tree::start_corner`](crate::Tree::start_corner).
+///
+/// Items starting at the bottom are rendered upwards.
+/// Items starting on the right are right aligned with their indentation growing leftwards and the highlight symbol on the right.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Corner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+impl Corner {
+    /// Whether the rows are laid out from the right edge.
+    pub(crate) const fn is_right(self) -> bool {
+        matches!(self, Self::TopRight | Self::BottomRight)
+    }
+
+    /// Whether the first item is at the bottom.
+    pub(crate) const fn is_bottom(self) -> bool {
+        matches!(self, Self::BottomLeft | Self::BottomRight)
+    }
+}
+
+/// Direction in which the rows are laid out, see [`Tree::direction`](crate::Tree::direction).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left to right: highlight symbol, indentation and node symbol on the left.
+    #[default]
+    Ltr,
+    /// Right to left: highlight symbol, indentation and node symbol on the right with the labels right aligned.
+    Rtl,
+}
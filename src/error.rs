@@ -0,0 +1,80 @@
+use std::fmt;
+
+/// Error when building or accessing the items of a [`Tree`](crate::Tree).
+///
+/// Converts into a [`std::io::Error`] so `?` keeps working in functions returning a [`std::io::Result`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TreeError {
+    /// Multiple items on the same level have the same content and therefore the same identifier.
+    ///
+    /// Converts into [`ErrorKind::AlreadyExists`](std::io::ErrorKind::AlreadyExists).
+    DuplicateIdentifier {
+        /// Identifier of the duplicate item, starting at the outermost item involved.
+        path: Vec<u64>,
+        /// Content of the items along the `path` for showing it to humans.
+        labels: Vec<String>,
+    },
+    /// Items with different content on the same level have the same identifier as their hashes collide.
+    ///
+    /// Converts into [`ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData).
+    IdentifierCollision {
+        /// Identifier of the colliding item, starting at the outermost item involved.
+        path: Vec<u64>,
+        /// Content of the items along the `path` for showing it to humans.
+        labels: Vec<String>,
+    },
+    /// The identifier does not lead to an item.
+    ///
+    /// Converts into [`ErrorKind::NotFound`](std::io::ErrorKind::NotFound).
+    InvalidPath {
+        /// The identifier which does not exist.
+        path: Vec<u64>,
+    },
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateIdentifier { labels, .. } => {
+                write!(f, "duplicate identifier at {labels:?}")
+            }
+            Self::IdentifierCollision { labels, .. } => {
+                write!(f, "identifiers of different items collide at {labels:?}")
+            }
+            Self::InvalidPath { path } => write!(f, "no item at {path:?}"),
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl From<TreeError> for std::io::Error {
+    fn from(error: TreeError) -> Self {
+        let kind = match error {
+            TreeError::DuplicateIdentifier { .. } => std::io::ErrorKind::AlreadyExists,
+            TreeError::IdentifierCollision { .. } => std::io::ErrorKind::InvalidData,
+            TreeError::InvalidPath { .. } => std::io::ErrorKind::NotFound,
+        };
+        Self::new(kind, error)
+    }
+}
+
+#[test]
+fn converts_into_io_error() {
+    let error = std::io::Error::from(TreeError::DuplicateIdentifier {
+        path: vec![1, 2],
+        labels: vec!["b".to_owned(), "d".to_owned()],
+    });
+    assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(error.to_string(), r#"duplicate identifier at ["b", "d"]"#);
+
+    let error = std::io::Error::from(TreeError::IdentifierCollision {
+        path: vec![3],
+        labels: vec!["e".to_owned()],
+    });
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let error = std::io::Error::from(TreeError::InvalidPath { path: vec![4] });
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
@@ -13,10 +13,14 @@ where
     T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
 {
     pub identifier: Vec<u64>,
+    /// Content of every item from the top level down to this item.
+    ///
+    /// Unlike the hashed `identifier` this can be matched against the original data.
+    pub identifier_path: Vec<&'a T>,
     pub item: &'a TreeItem<T>,
 }
 
-impl<'a, T> Flattened<'a, T>
+impl<T> Flattened<'_, T>
 where
     T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
 {
@@ -30,6 +34,7 @@ where
 /// Get a flat list of all visible [`TreeItem`]s.
 ///
 /// `current` starts empty: `&[]`
+/// The `identifier_path` of the result always starts at the given `items`.
 #[must_use]
 pub fn flatten<'a, T>(
     open_identifiers: &HashSet<Vec<u64>>,
@@ -39,23 +44,98 @@ pub fn flatten<'a, T>(
 where
     T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
 {
-    let mut result = Vec::new();
-    for item in items {
-        let mut child_identifier = current.to_vec();
+    // Every item on this level is visible. Open items reserve room for their children below.
+    let mut result = Vec::with_capacity(items.len());
+    for flattened in flatten_iter(open_identifiers, items, current) {
+        if flattened.item.has_children() && open_identifiers.contains(&flattened.identifier) {
+            result.reserve(flattened.item.children.len());
+        }
+        result.push(flattened);
+    }
+    result
+}
+
+/// Lazily iterate over all visible [`TreeItem`]s in the same order as [`flatten`].
+///
+/// Nothing is allocated for items which are never reached, so only a window can be taken with `skip` and `take`.
+pub fn flatten_iter<'a: 'b, 'b, T>(
+    open_identifiers: &'b HashSet<Vec<u64>>,
+    items: &'a [TreeItem<T>],
+    current: &[u64],
+) -> impl Iterator<Item = Flattened<'a, T>> + 'b
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    // Siblings still to be flattened together with the identifier of their parent.
+    // An explicit stack instead of recursion keeps deep trees from overflowing the call stack.
+    let mut stack = vec![(items.iter(), current.to_vec(), Vec::new())];
+    std::iter::from_fn(move || loop {
+        let (siblings, parent, parent_path) = stack.last_mut()?;
+        let Some(item) = siblings.next() else {
+            stack.pop();
+            continue;
+        };
+
+        let mut child_identifier = parent.clone();
         child_identifier.push(item.identifier);
+        let mut identifier_path = parent_path.clone();
+        identifier_path.push(&item.content);
 
-        let child_result = open_identifiers
-            .contains(&child_identifier)
-            .then(|| flatten(open_identifiers, &item.children, &child_identifier));
+        if open_identifiers.contains(&child_identifier) {
+            stack.push((
+                item.children.iter(),
+                child_identifier.clone(),
+                identifier_path.clone(),
+            ));
+        }
 
-        result.push(Flattened {
+        return Some(Flattened {
             identifier: child_identifier,
+            identifier_path,
             item,
         });
+    })
+}
 
-        if let Some(mut child_result) = child_result {
-            result.append(&mut child_result);
+/// Get a flat list of all [`TreeItem`]s matching the `filter` together with their ancestors.
+///
+/// The open state is ignored: every ancestor of a match is shown with its matching descendants.
+/// Descendants of a match which do not match themselves are hidden.
+#[must_use]
+pub fn flatten_filtered<'a, T, F>(items: &'a [TreeItem<T>], filter: &F) -> Vec<Flattened<'a, T>>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    F: Fn(&TreeItem<T>) -> bool + ?Sized,
+{
+    let mut result = Vec::new();
+    let mut identifier = Vec::new();
+    let mut identifier_path = Vec::new();
+    // Siblings still to be checked together with the index and item of their parent in the result.
+    // An explicit stack instead of recursion keeps deep trees from overflowing the call stack.
+    let mut stack = vec![(items.iter(), None)];
+    while let Some((siblings, parent)) = stack.last_mut() {
+        if let Some(item) = siblings.next() {
+            identifier.push(item.identifier);
+            identifier_path.push(&item.content);
+            let index = result.len();
+            result.push(Flattened {
+                identifier: identifier.clone(),
+                identifier_path: identifier_path.clone(),
+                item,
+            });
+            stack.push((item.children.iter(), Some((index, item))));
+            continue;
+        }
+
+        // All descendants are done, keep the parent only when something below it or itself matched
+        if let Some((index, item)) = *parent {
+            if result.len() == index + 1 && !filter(item) {
+                result.truncate(index);
+            }
+            identifier.pop();
+            identifier_path.pop();
         }
+        stack.pop();
     }
     result
 }
@@ -163,3 +243,122 @@ fn flatten_all_open() {
         ],
     );
 }
+
+#[test]
+fn flatten_deep_tree() {
+    const DEPTH: usize = 5000;
+    let mut item = TreeItem::new_leaf(DEPTH.to_string());
+    for depth in (0..DEPTH).rev() {
+        item = TreeItem::new(depth.to_string(), vec![item]).unwrap();
+    }
+    let items = vec![item];
+
+    let mut open = HashSet::new();
+    let mut identifier = Vec::new();
+    let mut current = &items[0];
+    loop {
+        identifier.push(current.identifier);
+        open.insert(identifier.clone());
+        match current.children.first() {
+            Some(child) => current = child,
+            None => break,
+        }
+    }
+
+    let result = flatten(&open, &items, &[]);
+    assert_eq!(result.len(), DEPTH + 1);
+    assert!(result
+        .iter()
+        .enumerate()
+        .all(|(depth, flattened)| flattened.depth() == depth));
+    assert_eq!(result[DEPTH].item.content(), &DEPTH.to_string());
+}
+
+#[test]
+fn flatten_filtered_deep_tree() {
+    const DEPTH: usize = 5000;
+    let mut item = TreeItem::new_leaf(DEPTH.to_string());
+    for depth in (0..DEPTH).rev() {
+        item = TreeItem::new(depth.to_string(), vec![item]).unwrap();
+    }
+    let items = vec![item];
+
+    let deepest = DEPTH.to_string();
+    let result = flatten_filtered(&items, &|item: &TreeItem<String>| {
+        item.content() == &deepest
+    });
+    assert_eq!(result.len(), DEPTH + 1);
+    assert_eq!(result[DEPTH].depth(), DEPTH);
+    assert_eq!(result[DEPTH].item.content(), &deepest);
+
+    let result = flatten_filtered(&items, &|_: &TreeItem<String>| false);
+    assert!(result.is_empty());
+}
+
+#[test]
+fn flatten_iter_matches_flatten() {
+    let items = TreeItem::example();
+    let mut open = HashSet::new();
+    open.insert(vec![items[1].identifier]);
+    open.insert(vec![items[1].identifier, items[1].children[1].identifier]);
+
+    let collected = flatten(&open, &items, &[]);
+    let iterated = flatten_iter(&open, &items, &[]).collect::<Vec<_>>();
+    assert_eq!(iterated.len(), collected.len());
+    for (a, b) in iterated.iter().zip(&collected) {
+        assert_eq!(a.identifier, b.identifier);
+        assert_eq!(a.identifier_path, b.identifier_path);
+        assert!(std::ptr::eq(a.item, b.item));
+    }
+
+    let window = flatten_iter(&open, &items, &[])
+        .skip(3)
+        .take(2)
+        .map(|flattened| *flattened.item.content())
+        .collect::<Vec<_>>();
+    assert_eq!(window, ["Delta", "Echo"]);
+}
+
+#[test]
+fn flatten_has_identifier_path() {
+    let items = TreeItem::example();
+    let mut open = HashSet::new();
+    open.insert(vec![items[1].identifier]);
+    open.insert(vec![items[1].identifier, items[1].children[1].identifier]);
+
+    let result = flatten(&open, &items, &[]);
+    assert_eq!(result[4].identifier_path, [&"Bravo", &"Delta", &"Echo"]);
+    assert!(result
+        .iter()
+        .all(|flattened| flattened.identifier_path.len() == flattened.depth() + 1));
+
+    let filtered = flatten_filtered(&items, &|item: &TreeItem<&str>| {
+        *item.content() == "Foxtrot"
+    });
+    assert_eq!(
+        filtered.last().unwrap().identifier_path,
+        [&"Bravo", &"Delta", &"Foxtrot"]
+    );
+}
+
+#[test]
+fn flatten_filtered_keeps_ancestors() {
+    let items = TreeItem::example();
+    let result = flatten_filtered(&items, &|item: &TreeItem<&str>| {
+        item.content().to_lowercase().contains('e')
+    });
+    let actual = result
+        .iter()
+        .map(|flattened| (*flattened.item.content(), flattened.depth()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actual,
+        [
+            ("Bravo", 0),
+            ("Charlie", 1),
+            ("Delta", 1),
+            ("Echo", 2),
+            ("Hotel", 0),
+        ]
+    );
+}
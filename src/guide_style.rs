@@ -0,0 +1,161 @@
+use ratatui::style::Style;
+use ratatui::text::ToText;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use crate::flatten::Flattened;
+
+/// Glyphs and style of the guide lines connecting parents with their children.
+///
+/// Enable them with [`Tree::guide_lines`](crate::Tree::guide_lines).
+/// Each glyph is drawn at the start of an indentation level and is cut off at the indentation width.
+///
+/// # Example
+///
+/// ```
+/// # use managarr_tree_widget::GuideStyle;
+/// # use ratatui::style::{Color, Style};
+/// let guides = GuideStyle::ASCII.style(Style::new().fg(Color::DarkGray));
+/// ```
+#[must_use]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuideStyle<'a> {
+    /// Drawn on the levels of ancestors which have following siblings.
+    pub vertical: &'a str,
+    /// Drawn in front of an item which has following siblings.
+    pub branch: &'a str,
+    /// Drawn in front of the last item of its siblings.
+    pub last_branch: &'a str,
+    /// Style of the glyphs
+    pub style: Style,
+}
+
+impl GuideStyle<'static> {
+    pub const UNICODE: Self = Self {
+        vertical: "\u{2502} ",           // │
+        branch: "\u{251c}\u{2500}",      // ├─
+        last_branch: "\u{2514}\u{2500}", // └─
+        style: Style::new(),
+    };
+
+    pub const ASCII: Self = Self {
+        vertical: "| ",
+        branch: "|-",
+        last_branch: "`-",
+        style: Style::new(),
+    };
+}
+
+impl Default for GuideStyle<'static> {
+    fn default() -> Self {
+        Self::UNICODE
+    }
+}
+
+impl<'a> GuideStyle<'a> {
+    pub const fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The `glyph` as seen in a mirror for rows laid out from the right edge.
+    pub(crate) fn mirror(glyph: &str) -> String {
+        glyph
+            .chars()
+            .rev()
+            .map(|character| match character {
+                '\u{251c}' => '\u{2524}', // ├ to ┤
+                '\u{2514}' => '\u{2518}', // └ to ┘
+                '`' => '\'',
+                other => other,
+            })
+            .collect()
+    }
+
+    /// The `glyph` upside down for rows laid out from the bottom edge.
+    pub(crate) fn flip(glyph: &str) -> String {
+        glyph
+            .chars()
+            .map(|character| match character {
+                '\u{2514}' => '\u{250c}', // └ to ┌
+                '\u{2518}' => '\u{2510}', // ┘ to ┐
+                '`' | '\'' => ',',
+                other => other,
+            })
+            .collect()
+    }
+
+    /// Glyphs in front of each of the `visible` items, one for each of its ancestor levels.
+    pub(crate) fn glyphs<T>(&self, visible: &[Flattened<T>]) -> Vec<Vec<&'a str>>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        // Walk backwards to know which items are followed by a sibling
+        let mut has_next_sibling = vec![false; visible.len()];
+        let mut seen_on_depth = Vec::new();
+        for (index, flattened) in visible.iter().enumerate().rev() {
+            let depth = flattened.depth();
+            seen_on_depth.resize(depth + 1, false);
+            has_next_sibling[index] = seen_on_depth[depth];
+            seen_on_depth[depth] = true;
+        }
+
+        // Whether the ancestor on the given depth is followed by a sibling
+        let mut ancestor_continues = Vec::new();
+        visible
+            .iter()
+            .zip(has_next_sibling)
+            .map(|(flattened, has_next_sibling)| {
+                let depth = flattened.depth();
+                ancestor_continues.truncate(depth);
+                let glyphs = (0..depth)
+                    .map(|level| {
+                        if level + 1 == depth {
+                            if has_next_sibling {
+                                self.branch
+                            } else {
+                                self.last_branch
+                            }
+                        } else if ancestor_continues[level + 1] {
+                            self.vertical
+                        } else {
+                            ""
+                        }
+                    })
+                    .collect();
+                ancestor_continues.push(has_next_sibling);
+                glyphs
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn mirror_glyphs() {
+    assert_eq!(
+        GuideStyle::mirror(GuideStyle::UNICODE.vertical),
+        " \u{2502}"
+    );
+    assert_eq!(
+        GuideStyle::mirror(GuideStyle::UNICODE.branch),
+        "\u{2500}\u{2524}"
+    );
+    assert_eq!(GuideStyle::mirror(GuideStyle::ASCII.last_branch), "-'");
+}
+
+#[test]
+fn flip_glyphs() {
+    assert_eq!(
+        GuideStyle::flip(GuideStyle::UNICODE.last_branch),
+        "\u{250c}\u{2500}"
+    );
+    assert_eq!(
+        GuideStyle::flip(&GuideStyle::mirror(GuideStyle::UNICODE.last_branch)),
+        "\u{2500}\u{2510}"
+    );
+    assert_eq!(GuideStyle::flip(GuideStyle::ASCII.last_branch), ",-");
+    assert_eq!(
+        GuideStyle::flip(GuideStyle::UNICODE.branch),
+        GuideStyle::UNICODE.branch
+    );
+}
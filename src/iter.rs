@@ -0,0 +1,117 @@
+use ratatui::text::ToText;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use crate::tree_item::TreeItem;
+
+/// Depth-first iterator over [`TreeItem`]s in pre-order together with their identifier.
+///
+/// Created with [`TreeItem::iter`] or [`iter_all`].
+/// Items are visited lazily so no list of all items is allocated up front.
+#[must_use]
+pub struct Iter<'a, T>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    stack: Vec<std::slice::Iter<'a, TreeItem<T>>>,
+    identifier: Vec<u64>,
+}
+
+impl<'a, T> Iter<'a, T>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    pub(crate) fn new(items: &'a [TreeItem<T>]) -> Self {
+        Self {
+            stack: vec![items.iter()],
+            identifier: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    type Item = (Vec<u64>, &'a TreeItem<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.stack.len().checked_sub(1)?;
+            if let Some(item) = self.stack[depth].next() {
+                self.identifier.truncate(depth);
+                self.identifier.push(item.identifier);
+                self.stack.push(item.children.iter());
+                return Some((self.identifier.clone(), item));
+            }
+            self.stack.pop();
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TreeItem<T>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    type Item = (Vec<u64>, &'a TreeItem<T>);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterate over all `items` and their children depth-first in pre-order.
+///
+/// # Example
+///
+/// ```
+/// # use managarr_tree_widget::{iter_all, TreeItem};
+/// let items = vec![TreeItem::new("Root", vec![TreeItem::new_leaf("Leaf")])?];
+/// assert_eq!(iter_all(&items).count(), 2);
+/// # Ok::<(), managarr_tree_widget::TreeError>(())
+/// ```
+pub fn iter_all<T>(items: &[TreeItem<T>]) -> Iter<'_, T>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    Iter::new(items)
+}
+
+#[test]
+fn iter_all_is_pre_order() {
+    let items = TreeItem::example();
+    let actual = iter_all(&items)
+        .map(|(identifier, item)| (identifier.len(), *item.content()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actual,
+        [
+            (1, "Alfa"),
+            (1, "Bravo"),
+            (2, "Charlie"),
+            (2, "Delta"),
+            (3, "Echo"),
+            (3, "Foxtrot"),
+            (2, "Golf"),
+            (1, "Hotel"),
+        ]
+    );
+}
+
+#[test]
+fn iter_yields_identifier_path() {
+    let items = TreeItem::example();
+    let bravo = &items[1];
+    let delta = &bravo.children()[1];
+    let (identifier, item) = bravo.iter().nth(3).unwrap();
+    assert_eq!(
+        identifier,
+        [
+            bravo.identifier(),
+            delta.identifier(),
+            delta.children()[0].identifier()
+        ]
+    );
+    assert_eq!(item.content(), &"Echo");
+}
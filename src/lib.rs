@@ -6,20 +6,40 @@ The user interaction state (like the current selection) is stored in the [`TreeS
 */
 
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Alignment, Rect};
 use ratatui::style::Style;
-use ratatui::text::ToText;
-use ratatui::widgets::{Block, Scrollbar, ScrollbarState, StatefulWidget, Widget};
-use std::collections::HashSet;
+use ratatui::text::{Span, Text, ToText};
+use ratatui::widgets::{
+    Block, HighlightSpacing, Scrollbar, ScrollbarState, StatefulWidget, Widget,
+};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::hash::Hash;
 use unicode_width::UnicodeWidthStr;
 
+use crate::callback::{Callback, CountFormat, DepthStyle, ItemFilter, ItemPrefix};
+use crate::flatten::flatten_filtered;
+use crate::row::RowCursor;
+use crate::tree_item::{duplicate_error, find_duplicate, get_item};
+use crate::tree_state::RenderedItem;
+
+pub use crate::corner::{Corner, TextDirection};
+pub use crate::error::TreeError;
 pub use crate::flatten::Flattened;
-pub use crate::tree_item::TreeItem;
-pub use crate::tree_state::TreeState;
+pub use crate::guide_style::GuideStyle;
+pub use crate::iter::{iter_all, Iter};
+pub use crate::tree_item::{item_count, leaf_count, TreeItem};
+pub use crate::tree_state::{ToggleAction, ToggleHit, TreeState};
 
+mod callback;
+mod corner;
+mod error;
 mod flatten;
+mod guide_style;
+mod iter;
+mod line;
+mod row;
 mod tree_item;
 mod tree_state;
 
@@ -51,6 +71,7 @@ mod tree_state;
 /// ```
 #[must_use]
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Tree<'a, T>
 where
     T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
@@ -58,7 +79,11 @@ where
     items: &'a [TreeItem<T>],
 
     block: Option<Block<'a>>,
+    /// Amount of all items together with its format appended to the title of the `block`
+    block_count: Option<(usize, CountFormat<'a>)>,
     scrollbar: Option<Scrollbar<'a>>,
+    /// Draw the scrollbar inside the inner area instead of onto the block border
+    scrollbar_inside: bool,
     /// Style used as a base style for the widget
     style: Style,
 
@@ -66,6 +91,14 @@ where
     highlight_style: Style,
     /// Symbol in front of the selected item (Shift all items to the right)
     highlight_symbol: &'a str,
+    /// Symbol in front of the selected item when it is an open node instead of the `highlight_symbol`
+    highlight_symbol_open: Option<&'a str>,
+    /// Symbol in front of the selected item when it is a closed node instead of the `highlight_symbol`
+    highlight_symbol_closed: Option<&'a str>,
+    /// Apply the `highlight_style` to the whole row instead of only the text
+    highlight_row: bool,
+    /// When to reserve the columns of the highlight symbol
+    highlight_spacing: HighlightSpacing,
 
     /// Symbol displayed in front of a closed node (As in the children are currently not visible)
     node_closed_symbol: &'a str,
@@ -73,6 +106,67 @@ where
     node_open_symbol: &'a str,
     /// Symbol displayed in front of a node without children.
     node_no_children_symbol: &'a str,
+
+    /// Amount of columns each depth level is indented by
+    indent: u16,
+    /// Guide lines drawn into the indentation
+    guide_lines: Option<GuideStyle<'a>>,
+    /// Reserve the node symbol column for items without children
+    align_leaves: bool,
+    /// Style of the ancestors of the selected item
+    lineage_style: Style,
+    /// Style of the items outside of the branch of the selected item
+    dim_unrelated: Option<Style>,
+    /// Style patched onto the guide lines
+    guide_style: Style,
+    /// Highlight the guide lines of highlighted items
+    guides_follow_highlight: bool,
+    /// Wrap labels which are wider than the available width onto additional lines
+    wrap: bool,
+    /// Only show items matching this together with their ancestors
+    filter: Option<ItemFilter<'a, T>>,
+    /// Text highlighted within the labels. Empty to highlight nothing
+    match_query: &'a str,
+    /// Style patched onto the `match_query` within the labels
+    highlight_match_style: Style,
+    /// Show the amount of children after the label of closed nodes
+    closed_child_count: bool,
+    /// Decoration between the node symbol and the label
+    prefix: Option<ItemPrefix<'a, T>>,
+    /// Base style of the labels depending on their depth
+    label_base_style: Option<DepthStyle<'a>>,
+    /// Style of the [`TreeItem::icon`]
+    icon_style: Style,
+    /// Styles of the indentation, one per depth level and repeated for deeper levels
+    depth_styles: Vec<Style>,
+    /// Base styles of the even and odd visible items
+    row_styles: Option<(Style, Style)>,
+    /// Whether the tree is the active part of the interface
+    focused: bool,
+    /// Style used to render the selected item while not focused
+    inactive_style: Style,
+    /// Corner of the area in which the first item is rendered
+    start_corner: Corner,
+    /// Direction in which the rows are laid out
+    direction: TextDirection,
+    /// Amount of items kept visible above and below the selection when scrolling it into view
+    scrolloff: u16,
+    /// Amount of items this view is scrolled further than the offset of the state
+    viewport_offset: usize,
+    /// Moving past the last item selects the first one and the other way around
+    wrap_navigation: bool,
+    /// Open nodes when they get selected
+    open_on_select: bool,
+    /// Checked, unchecked and partially checked symbols in front of the labels
+    checkbox_symbols: Option<(&'a str, &'a str, &'a str)>,
+    /// Where the node symbol is placed in the row
+    symbol_alignment: Alignment,
+    /// Keep the parent of the first item on the top line when it scrolled out of view
+    sticky_ancestors: bool,
+    /// Style of the sticky parent on the top line
+    sticky_style: Style,
+    /// Amount of descendants of newly opened nodes additionally shown on each render. `0` shows them at once
+    reveal_step: usize,
 }
 
 impl<'a, T> Tree<'a, T>
@@ -83,38 +177,120 @@ where
     ///
     /// # Errors
     ///
-    /// Errors when there are duplicate identifiers in the children.
-    pub fn new(items: &'a [TreeItem<T>]) -> std::io::Result<Self> {
-        let identifiers = items
-            .iter()
-            .map(|item| &item.identifier)
-            .collect::<HashSet<_>>();
-        if identifiers.len() != items.len() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::AlreadyExists,
-                "The items contain duplicate identifiers",
-            ));
-        }
-
+    /// Errors with [`TreeError::DuplicateIdentifier`] when there are duplicate identifiers in the items.
+    /// Different items whose identifiers collide error with [`TreeError::IdentifierCollision`].
+    pub fn new(items: &'a [TreeItem<T>]) -> Result<Self, TreeError> {
+        validate_top_level(items)?;
         Ok(Self {
             items,
             block: None,
+            block_count: None,
             scrollbar: None,
+            scrollbar_inside: false,
             style: Style::new(),
             highlight_style: Style::new(),
             highlight_symbol: "",
+            highlight_symbol_open: None,
+            highlight_symbol_closed: None,
+            highlight_spacing: HighlightSpacing::WhenSelected,
+            highlight_row: true,
             node_closed_symbol: "\u{25b6} ", // Arrow to right
             node_open_symbol: "\u{25bc} ",   // Arrow down
             node_no_children_symbol: "  ",
+            indent: 2,
+            guide_lines: None,
+            align_leaves: true,
+            lineage_style: Style::new(),
+            dim_unrelated: None,
+            guide_style: Style::new(),
+            guides_follow_highlight: true,
+            wrap: false,
+            filter: None,
+            match_query: "",
+            highlight_match_style: Style::new(),
+            closed_child_count: false,
+            prefix: None,
+            label_base_style: None,
+            icon_style: Style::new(),
+            depth_styles: Vec::new(),
+            row_styles: None,
+            focused: true,
+            inactive_style: Style::new(),
+            start_corner: Corner::TopLeft,
+            direction: TextDirection::Ltr,
+            scrolloff: 0,
+            viewport_offset: 0,
+            wrap_navigation: false,
+            open_on_select: false,
+            checkbox_symbols: None,
+            symbol_alignment: Alignment::Left,
+            sticky_ancestors: false,
+            sticky_style: Style::new(),
+            reveal_step: 0,
         })
     }
 
+    /// Replace the items while keeping every other configuration of this `Tree`.
+    ///
+    /// # Errors
+    ///
+    /// Errors the same way as [`Tree::new`] when there are duplicate identifiers in the `items`.
+    pub fn items(mut self, items: &'a [TreeItem<T>]) -> Result<Self, TreeError> {
+        validate_top_level(items)?;
+        self.items = items;
+        if let Some((count, _)) = &mut self.block_count {
+            *count = item_count(items);
+        }
+        Ok(self)
+    }
+
+    /// The items of this `Tree`.
+    #[must_use]
+    pub const fn get_items(&self) -> &'a [TreeItem<T>] {
+        self.items
+    }
+
+    /// Check the identifiers on every level of the items to be unique.
+    ///
+    /// [`Tree::new`] and [`TreeItem::new`] only check their direct children.
+    /// Children added later with [`TreeItem::add_child`] are not checked against the rest of the tree.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`TreeError::DuplicateIdentifier`] when there are duplicate identifiers.
+    /// The path is the identifier of the duplicate item as used by the [`TreeState`] together with the content of its items.
+    /// Different items whose identifiers collide error with [`TreeError::IdentifierCollision`].
+    pub fn validate(&self) -> Result<(), TreeError> {
+        validate_unique(self.items, &mut Vec::new(), &mut Vec::new())
+    }
+
     #[allow(clippy::missing_const_for_fn)]
     pub fn block(mut self, block: Block<'a>) -> Self {
         self.block = Some(block);
         self
     }
 
+    /// Surround the items with the `block` and append the amount of all items as another title.
+    ///
+    /// The amount includes all descendants no matter whether they are open.
+    /// It is counted once here and again when replacing the items with [`items`](Self::items), only the formatting happens on render.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::{Tree, TreeItem};
+    /// # use ratatui::widgets::Block;
+    /// # let items = vec![TreeItem::new_leaf("a")];
+    /// let tree = Tree::new(&items)?.block_with_count(Block::bordered().title("Files"), |count| format!("({count})"));
+    /// # Ok::<(), managarr_tree_widget::TreeError>(())
+    /// ```
+    pub fn block_with_count<F>(mut self, block: Block<'a>, format: F) -> Self
+    where
+        F: Fn(usize) -> String + 'a,
+    {
+        self.block = Some(block);
+        self.block_count = Some((item_count(self.items), Callback(std::rc::Rc::new(format))));
+        self
+    }
+
     /// Show the scrollbar when rendering this widget.
     ///
     /// Experimental: Can change on any release without any additional notice.
@@ -122,6 +298,18 @@ where
     /// Also see <https://github.com/ratatui-org/ratatui/issues/174>
     pub const fn experimental_scrollbar(mut self, scrollbar: Option<Scrollbar<'a>>) -> Self {
         self.scrollbar = scrollbar;
+        self.scrollbar_inside = false;
+        self
+    }
+
+    /// Draw a vertical scrollbar on the right column of the area inside a possible block.
+    ///
+    /// The column is always reserved for the scrollbar so the items do not move when the scrollbar appears.
+    /// When all items fit into the area it's up to the [`Scrollbar`] what is shown.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn scrollbar(mut self, scrollbar: Scrollbar<'a>) -> Self {
+        self.scrollbar = Some(scrollbar);
+        self.scrollbar_inside = true;
         self
     }
 
@@ -140,6 +328,114 @@ where
         self
     }
 
+    /// When to reserve the columns of the highlight symbol in front of the items.
+    ///
+    /// Defaults to [`HighlightSpacing::WhenSelected`].
+    /// [`HighlightSpacing::Always`] keeps the items from shifting when the first item is selected.
+    /// [`HighlightSpacing::Never`] does not show the highlight symbol at all.
+    pub const fn highlight_spacing(mut self, spacing: HighlightSpacing) -> Self {
+        self.highlight_spacing = spacing;
+        self
+    }
+
+    /// Symbol in front of the selected item when it is an open node.
+    ///
+    /// Falls back to the [`highlight_symbol`](Self::highlight_symbol) when not set.
+    pub const fn highlight_symbol_open(mut self, highlight_symbol: &'a str) -> Self {
+        self.highlight_symbol_open = Some(highlight_symbol);
+        self
+    }
+
+    /// Symbol in front of the selected item when it is a closed node.
+    ///
+    /// Falls back to the [`highlight_symbol`](Self::highlight_symbol) when not set.
+    pub const fn highlight_symbol_closed(mut self, highlight_symbol: &'a str) -> Self {
+        self.highlight_symbol_closed = Some(highlight_symbol);
+        self
+    }
+
+    /// Corner of the area in which the first item is rendered. Defaults to [`Corner::TopLeft`].
+    ///
+    /// Starting at the bottom renders the items upwards.
+    /// Starting on the right aligns the items to the right with the indentation growing leftwards and the `highlight_symbol` on the right.
+    pub const fn start_corner(mut self, corner: Corner) -> Self {
+        self.start_corner = corner;
+        self
+    }
+
+    /// Direction in which the rows are laid out. Defaults to [`TextDirection::Ltr`].
+    ///
+    /// [`TextDirection::Rtl`] is meant for right-to-left scripts like Arabic or Hebrew.
+    /// The highlight symbol, the indentation and the node symbols are placed at the right edge and the labels are right aligned.
+    /// The vertical order is still controlled by the [`start_corner`](Self::start_corner).
+    pub const fn direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Where to place the node symbols in the row. Defaults to [`Alignment::Left`].
+    ///
+    /// With [`Alignment::Right`] the symbols are drawn after the labels flush against the end of the row.
+    /// The indentation stays in front of the labels. [`Alignment::Center`] is handled like [`Alignment::Left`].
+    pub const fn symbol_alignment(mut self, alignment: Alignment) -> Self {
+        self.symbol_alignment = alignment;
+        self
+    }
+
+    /// Keep the parent of the first item on the top line when it scrolled out of view.
+    ///
+    /// This shows which branch the items belong to while scrolling through deep trees.
+    /// The parent takes one line of the area.
+    pub const fn sticky_ancestors(mut self, sticky: bool) -> Self {
+        self.sticky_ancestors = sticky;
+        self
+    }
+
+    /// Style of the sticky parent, see [`sticky_ancestors`](Self::sticky_ancestors).
+    pub const fn sticky_style(mut self, style: Style) -> Self {
+        self.sticky_style = style;
+        self
+    }
+
+    /// Reveal the children of newly opened nodes gradually instead of all at once.
+    ///
+    /// Each render shows `step` more of their visible descendants until all of them are shown.
+    /// This requires rendering regularly while [`TreeState::is_revealing`].
+    /// Defaults to `0` which shows them instantly.
+    pub const fn reveal_step(mut self, step: usize) -> Self {
+        self.reveal_step = step;
+        self
+    }
+
+    /// Whether the rows are laid out from the right edge.
+    const fn is_mirrored(&self) -> bool {
+        self.start_corner.is_right() || matches!(self.direction, TextDirection::Rtl)
+    }
+
+    /// Whether the tree is the focused part of the interface. Defaults to `true`.
+    ///
+    /// While not focused the selected items use the [`inactive_style`](Self::inactive_style) instead of the `highlight_style` and the `highlight_symbol` is hidden.
+    /// This keeps the selection visible without looking like the active cursor.
+    pub const fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Style used to render the selected items while not [`focused`](Self::focused).
+    pub const fn inactive_style(mut self, style: Style) -> Self {
+        self.inactive_style = style;
+        self
+    }
+
+    /// Apply the `highlight_style` to the whole row including the indentation and symbols.
+    /// Otherwise only the text is highlighted. Defaults to `true`.
+    ///
+    /// Items spanning multiple lines are highlighted on all of their lines either way.
+    pub const fn highlight_row(mut self, highlight_row: bool) -> Self {
+        self.highlight_row = highlight_row;
+        self
+    }
+
     pub const fn node_closed_symbol(mut self, symbol: &'a str) -> Self {
         self.node_closed_symbol = symbol;
         self
@@ -154,10 +450,373 @@ where
         self.node_no_children_symbol = symbol;
         self
     }
+
+    /// Amount of columns each depth level is indented by. Defaults to 2.
+    pub const fn indent(mut self, spaces: u16) -> Self {
+        self.indent = spaces;
+        self
+    }
+
+    /// Style of the rows of all ancestors of the selected item.
+    ///
+    /// This shows where the selected item sits in the tree.
+    pub const fn lineage_style(mut self, style: Style) -> Self {
+        self.lineage_style = style;
+        self
+    }
+
+    /// Style of the rows which are neither the selected item, one of its ancestors nor one of its descendants.
+    ///
+    /// This draws attention to the branch of the selected item. Nothing is dimmed without a selection.
+    pub const fn dim_unrelated(mut self, style: Style) -> Self {
+        self.dim_unrelated = Some(style);
+        self
+    }
+
+    /// Whether items without children reserve the columns of the node symbol. Defaults to `true`.
+    ///
+    /// When `false` their labels start where the node symbol would be, which is more compact.
+    pub const fn align_leaves(mut self, align: bool) -> Self {
+        self.align_leaves = align;
+        self
+    }
+
+    /// Draw guide lines connecting parents with their children into the indentation.
+    pub const fn guide_lines(mut self, guides: GuideStyle<'a>) -> Self {
+        self.guide_lines = Some(guides);
+        self
+    }
+
+    /// Style patched onto the guide lines after the style of their [`GuideStyle`].
+    ///
+    /// Only the guide glyphs are styled with it, the labels and node symbols are not.
+    pub const fn guide_style(mut self, style: Style) -> Self {
+        self.guide_style = style;
+        self
+    }
+
+    /// Whether the guide lines of highlighted items get the highlight style too. Defaults to `true`.
+    ///
+    /// When `false` the guides keep their own style even with [`highlight_row`](Self::highlight_row).
+    pub const fn guides_follow_highlight(mut self, follow: bool) -> Self {
+        self.guides_follow_highlight = follow;
+        self
+    }
+
+    /// Base style of the labels depending on their depth.
+    ///
+    /// It's applied before the label is drawn, so the [`TreeItem::style`] of an item stays on top of it.
+    /// The [`highlight_style`](Self::highlight_style) is still applied on top.
+    pub fn label_base_style_fn<F>(mut self, style: F) -> Self
+    where
+        F: Fn(usize) -> Style + 'a,
+    {
+        self.label_base_style = Some(Callback(std::rc::Rc::new(style)));
+        self
+    }
+
+    /// Tint the indentation of each depth level differently.
+    ///
+    /// The columns of depth level `d` including their guide lines are patched with `styles[d % styles.len()]`.
+    /// No styles leave the indentation unchanged.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn depth_styles(mut self, styles: Vec<Style>) -> Self {
+        self.depth_styles = styles;
+        self
+    }
+
+    /// Alternate the base style of the visible items between `even` and `odd` for zebra striping.
+    ///
+    /// The stripes follow the position among all visible items so they stay with the items while scrolling.
+    /// Items spanning multiple lines are striped as one block. The item style and the highlight are applied on top.
+    pub const fn row_styles(mut self, even: Style, odd: Style) -> Self {
+        self.row_styles = Some((even, odd));
+        self
+    }
+
+    /// Show the items this amount further down than the offset of the [`TreeState`].
+    ///
+    /// This allows multiple views sharing one state to show different parts of the items, for example in split panes.
+    /// Revealing the selection moves the offset of the state and therefore every view.
+    /// Otherwise only views without a `viewport_offset` clamp the offset of the state to the items.
+    pub const fn viewport_offset(mut self, offset: usize) -> Self {
+        self.viewport_offset = offset;
+        self
+    }
+
+    /// Keep at least this amount of items visible above and below the selection when it is scrolled into view, like `scrolloff` in Vim.
+    ///
+    /// Near the start and end of the tree or in small areas the margin shrinks.
+    pub const fn scrolloff(mut self, scrolloff: u16) -> Self {
+        self.scrolloff = scrolloff;
+        self
+    }
+
+    /// Whether [`TreeState::key_down`] on the last item selects the first one and [`TreeState::key_up`] on the first item the last one.
+    /// Defaults to `false` which stops at the ends.
+    ///
+    /// The setting is remembered in the [`TreeState`] on render.
+    pub const fn wrap_navigation(mut self, wrap_navigation: bool) -> Self {
+        self.wrap_navigation = wrap_navigation;
+        self
+    }
+
+    /// Open a node with children as soon as it gets selected, for example by [`TreeState::key_down`]. Defaults to `false`.
+    ///
+    /// Closing the selected node again with [`TreeState::key_left`] keeps it closed.
+    /// The setting is remembered in the [`TreeState`] on render.
+    pub const fn open_on_select(mut self, open_on_select: bool) -> Self {
+        self.open_on_select = open_on_select;
+        self
+    }
+
+    /// Wrap labels which are wider than the available width onto additional lines instead of cutting them off.
+    ///
+    /// The wrapped lines start below the start of the label and make the item taller, which scrolling takes into account.
+    /// As the labels are shown completely, horizontal scrolling is disabled while wrapping.
+    pub const fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Only show the items matching the `filter` and the ancestors of them.
+    ///
+    /// Ancestors of matches are shown open no matter what is open in the [`TreeState`].
+    /// The items themselves are not changed, the filter is applied while rendering.
+    /// Navigating with the [`TreeState`] based on the last render (like [`key_down`](TreeState::key_down)) only moves between the shown items.
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&TreeItem<T>) -> bool + 'a,
+    {
+        self.filter = Some(Callback(std::rc::Rc::new(filter)));
+        self
+    }
+
+    /// Highlight the first occurrence of the `query` in every line of the labels with the [`highlight_match_style`](Self::highlight_match_style).
+    ///
+    /// The `query` is matched case-insensitively. An empty `query` highlights nothing.
+    pub const fn match_query(mut self, query: &'a str) -> Self {
+        self.match_query = query;
+        self
+    }
+
+    /// Style of the [`match_query`](Self::match_query) within the labels.
+    ///
+    /// On highlighted items this is patched over the [`highlight_style`](Self::highlight_style).
+    pub const fn highlight_match_style(mut self, style: Style) -> Self {
+        self.highlight_match_style = style;
+        self
+    }
+
+    /// Show the amount of direct children like ` (3)` after the label of closed nodes.
+    ///
+    /// Leaves and open nodes show nothing.
+    pub const fn closed_child_count(mut self, show: bool) -> Self {
+        self.closed_child_count = show;
+        self
+    }
+
+    /// Style of the [`TreeItem::icon`]s. It is patched over the style of the item content.
+    pub const fn icon_style(mut self, style: Style) -> Self {
+        self.icon_style = style;
+        self
+    }
+
+    /// Draw a decoration like a git status letter or a file type icon between the node symbol and the label.
+    ///
+    /// The closure gets the item and its zero based depth.
+    pub fn prefix<F>(mut self, prefix: F) -> Self
+    where
+        F: Fn(&TreeItem<T>, usize) -> Span<'a> + 'a,
+    {
+        self.prefix = Some(Callback(std::rc::Rc::new(prefix)));
+        self
+    }
+
+    /// Show a checkbox in front of the labels with the given symbols.
+    ///
+    /// Nodes are checked with [`TreeState::set_checked`].
+    /// The `partial` symbol is shown for nodes where only some of the descendants are checked.
+    pub const fn checkbox_symbols(
+        mut self,
+        checked: &'a str,
+        unchecked: &'a str,
+        partial: &'a str,
+    ) -> Self {
+        self.checkbox_symbols = Some((checked, unchecked, partial));
+        self
+    }
+
+    /// Checkbox in front of the label depending on the checked state of the item.
+    ///
+    /// `partially_checked` are the identifiers of [`TreeState::partially_checked`].
+    fn checkbox_symbol(
+        &self,
+        flattened: &Flattened<T>,
+        state: &TreeState,
+        partially_checked: &HashSet<Vec<u64>>,
+    ) -> Option<&'a str> {
+        let (checked, unchecked, partial) = self.checkbox_symbols?;
+        Some(if state.checked.contains(&flattened.identifier) {
+            checked
+        } else if partially_checked.contains(&flattened.identifier) {
+            partial
+        } else {
+            unchecked
+        })
+    }
+
+    /// Whether the columns of the highlight symbol are reserved in front of every item.
+    fn reserves_highlight_symbol(&self, state: &TreeState) -> bool {
+        match self.highlight_spacing {
+            HighlightSpacing::Always => true,
+            HighlightSpacing::WhenSelected => !state.selected.is_empty(),
+            HighlightSpacing::Never => false,
+        }
+    }
+
+    /// Highlight symbol of the selected item depending on whether it has children and is open.
+    fn highlight_symbol_for(&self, item: &TreeItem<T>, is_open: bool) -> &'a str {
+        let symbol = if item.is_leaf() {
+            None
+        } else if is_open {
+            self.highlight_symbol_open
+        } else {
+            self.highlight_symbol_closed
+        };
+        symbol.unwrap_or(self.highlight_symbol)
+    }
+
+    /// Width of the widest highlight symbol which is reserved in front of every item.
+    fn highlight_symbol_width(&self) -> usize {
+        [self.highlight_symbol_open, self.highlight_symbol_closed]
+            .into_iter()
+            .flatten()
+            .map(UnicodeWidthStr::width)
+            .fold(self.highlight_symbol.width(), usize::max)
+    }
+
+    /// Width of the widest node symbol which is reserved for every item.
+    fn node_symbol_width(&self) -> usize {
+        self.node_closed_symbol
+            .width()
+            .max(self.node_open_symbol.width())
+            .max(self.node_no_children_symbol.width())
+    }
+
+    /// Symbol in front of the item depending on whether it has children and is open.
+    fn node_symbol(&self, item: &TreeItem<T>, is_open: bool) -> &'a str {
+        if item.is_leaf() {
+            self.node_no_children_symbol
+        } else if is_open {
+            self.node_open_symbol
+        } else {
+            self.node_closed_symbol
+        }
+    }
+
+    /// Columns needed by the widest visible item, see [`TreeState::max_visible_width`].
+    pub(crate) fn max_visible_width(&self, state: &TreeState) -> usize {
+        let visible = self.filter.as_ref().map_or_else(
+            || state.flatten(self.items),
+            |filter| flatten_filtered(self.items, &**filter),
+        );
+        // An item is open when its children follow it. This includes ancestors opened by the filter.
+        let is_open = |index: usize| {
+            visible
+                .get(index + 1)
+                .is_some_and(|next| next.depth() > visible[index].depth())
+        };
+        let partially_checked = self.partially_checked(state);
+        visible
+            .iter()
+            .enumerate()
+            .map(|(index, flattened)| {
+                self.label_offset(flattened, state, &partially_checked)
+                    + self.label(flattened.item, is_open(index)).width()
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Label of the item as rendered, with the amount of children appended to closed nodes when enabled.
+    fn label<'t>(&self, item: &'t TreeItem<T>, is_open: bool) -> Text<'t> {
+        let mut text = item.content.to_text();
+        if self.closed_child_count && item.has_children() && !is_open {
+            let badge = format!(" ({})", item.children.len());
+            match text.lines.last_mut() {
+                Some(line) => line.spans.push(badge.into()),
+                None => text.lines.push(badge.into()),
+            }
+        }
+        text
+    }
+
+    /// Nodes to draw with the partial checkbox, only computed when [`checkbox_symbols`](Self::checkbox_symbols) are drawn.
+    fn partially_checked(&self, state: &TreeState) -> HashSet<Vec<u64>> {
+        if self.checkbox_symbols.is_some() {
+            state.partially_checked(self.items)
+        } else {
+            HashSet::new()
+        }
+    }
+
+    /// Height of the item when rendered in a row of the given width.
+    fn item_height(
+        &self,
+        flattened: &Flattened<T>,
+        state: &TreeState,
+        partially_checked: &HashSet<Vec<u64>>,
+        row_width: u16,
+        is_open: bool,
+    ) -> usize {
+        if !self.wrap {
+            return flattened.item.height();
+        }
+
+        let label_offset = self.label_offset(flattened, state, partially_checked);
+        let text_width = row_width.saturating_sub(u16::try_from(label_offset).unwrap_or(u16::MAX));
+        self.label(flattened.item, is_open)
+            .lines
+            .into_iter()
+            .map(|line| line::wrap(line, text_width as usize).len())
+            .sum()
+    }
+
+    /// Columns in front of the label: highlight symbol, indentation, node symbol and decorations.
+    fn label_offset(
+        &self,
+        flattened: &Flattened<T>,
+        state: &TreeState,
+        partially_checked: &HashSet<Vec<u64>>,
+    ) -> usize {
+        let highlight_width = if self.reserves_highlight_symbol(state) {
+            self.highlight_symbol_width()
+        } else {
+            0
+        };
+        let symbol_width = if flattened.item.is_leaf() && !self.align_leaves {
+            0
+        } else {
+            self.node_symbol_width()
+        };
+        let decoration_width = self.prefix.as_ref().map_or(0, |prefix| {
+            prefix(flattened.item, flattened.depth()).width()
+        });
+        let icon_width = flattened.item.icon.map_or(0, UnicodeWidthStr::width)
+            + self
+                .checkbox_symbol(flattened, state, partially_checked)
+                .map_or(0, UnicodeWidthStr::width);
+        highlight_width
+            + flattened.depth() * self.indent as usize
+            + symbol_width
+            + icon_width
+            + decoration_width
+    }
 }
 
 #[test]
-#[should_panic = "duplicate identifiers"]
+#[should_panic = "DuplicateIdentifier"]
 fn tree_new_errors_with_duplicate_identifiers() {
     let item = TreeItem::new_leaf("text".to_owned());
     let another = item.clone();
@@ -165,168 +824,564 @@ fn tree_new_errors_with_duplicate_identifiers() {
     let _ = Tree::new(&items).unwrap();
 }
 
-impl<'a, T> StatefulWidget for Tree<'a, T>
+impl<T> Default for Tree<'_, T>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    /// A `Tree` without items. Use [`Tree::items`] to set them.
+    fn default() -> Self {
+        Self::new(&[]).expect("no items can not contain duplicates")
+    }
+}
+
+/// Check the identifiers of the top level `items` to be unique like [`Tree::new`] and [`Tree::items`] do.
+fn validate_top_level<T>(items: &[TreeItem<T>]) -> Result<(), TreeError>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    match find_duplicate(items) {
+        Some((existing, duplicate)) => Err(duplicate_error(
+            existing,
+            duplicate,
+            vec![duplicate.identifier],
+            vec![duplicate.content.to_string()],
+        )),
+        None => Ok(()),
+    }
+}
+
+fn validate_unique<T>(
+    items: &[TreeItem<T>],
+    path: &mut Vec<u64>,
+    labels: &mut Vec<String>,
+) -> Result<(), TreeError>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    let mut identifiers = HashMap::with_capacity(items.len());
+    for item in items {
+        path.push(item.identifier);
+        labels.push(item.content.to_string());
+        if let Some(existing) = identifiers.insert(item.identifier, item) {
+            return Err(duplicate_error(
+                existing,
+                item,
+                path.clone(),
+                labels.clone(),
+            ));
+        }
+        validate_unique(&item.children, path, labels)?;
+        path.pop();
+        labels.pop();
+    }
+    Ok(())
+}
+
+#[test]
+fn tree_items_keeps_configuration() {
+    let first = [TreeItem::new_leaf("a")];
+    let second = [TreeItem::new_leaf("b"), TreeItem::new_leaf("c")];
+    let tree = Tree::new(&first)
+        .unwrap()
+        .highlight_symbol(">> ")
+        .indent(4)
+        .items(&second)
+        .unwrap();
+    assert_eq!(tree.get_items(), second);
+    assert_eq!(tree.highlight_symbol, ">> ");
+    assert_eq!(tree.indent, 4);
+
+    let duplicates = [TreeItem::new_leaf("d"), TreeItem::new_leaf("d")];
+    let expected = TreeError::DuplicateIdentifier {
+        path: vec![duplicates[0].identifier()],
+        labels: vec!["d".to_owned()],
+    };
+    assert_eq!(tree.items(&duplicates).unwrap_err(), expected);
+
+    let tree = Tree::default().items(&second).unwrap();
+    assert_eq!(tree.items.len(), 2);
+    assert_eq!(Tree::default().items(&duplicates).unwrap_err(), expected);
+}
+
+#[test]
+fn tree_validate_finds_nested_duplicate_identifiers() {
+    let mut items = vec![
+        TreeItem::new_leaf("a"),
+        TreeItem::new(
+            "b",
+            vec![TreeItem::new("d", vec![TreeItem::new_leaf("e")]).unwrap()],
+        )
+        .unwrap(),
+    ];
+    assert!(Tree::new(&items).unwrap().validate().is_ok());
+
+    items[1].children[0].children.push(TreeItem::new_leaf("e"));
+    let error = Tree::new(&items).unwrap().validate().unwrap_err();
+    let path = vec![
+        items[1].identifier,
+        items[1].children[0].identifier,
+        items[1].children[0].children[1].identifier,
+    ];
+    let labels = vec!["b".to_owned(), "d".to_owned(), "e".to_owned()];
+    assert_eq!(error, TreeError::DuplicateIdentifier { path, labels });
+    assert!(error.to_string().contains(r#"["b", "d", "e"]"#));
+}
+
+impl<T> StatefulWidget for Tree<'_, T>
 where
     T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
 {
     type State = TreeState;
 
     #[allow(clippy::too_many_lines)]
-    fn render(self, full_area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+    fn render(mut self, full_area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         buf.set_style(full_area, self.style);
 
         // Get the inner area inside a possible block, otherwise use the full area
-        let area = self.block.map_or(full_area, |block| {
+        let count_title = self.block_count.take().map(|(count, format)| format(count));
+        let area = self.block.take().map_or(full_area, |block| {
+            let block = match count_title {
+                Some(title) => block.title(title),
+                None => block,
+            };
             let inner_area = block.inner(full_area);
             block.render(full_area, buf);
             inner_area
         });
+        let (area, scrollbar_area) = if self.scrollbar.is_some() && self.scrollbar_inside {
+            let width = area.width.saturating_sub(1);
+            let scrollbar_area = Rect {
+                x: area.x + width,
+                width: area.width - width,
+                ..area
+            };
+            (Rect { width, ..area }, scrollbar_area)
+        } else {
+            let scrollbar_area = Rect {
+                // Inner height to be exactly as the content
+                y: area.y,
+                height: area.height,
+                // Outer width to stay on the right border
+                x: full_area.x,
+                width: full_area.width,
+            };
+            (area, scrollbar_area)
+        };
 
         state.last_area = area;
-        state.last_rendered_identifiers.clear();
+        state.last_wrap_navigation = self.wrap_navigation;
+        state.last_open_on_select = self.open_on_select;
+        state.last_identifiers.clear();
+        state.last_heights.clear();
+        state.last_selectable.clear();
+        state.last_has_children.clear();
+        state.last_indices.clear();
+        state.last_rendered.clear();
         if area.width < 1 || area.height < 1 {
             return;
         }
 
-        let visible = state.flatten(self.items);
+        let reveal = state
+            .scroll_to_on_next_render
+            .take()
+            .filter(|identifier| get_item(self.items, identifier).is_some());
+        if let Some(identifier) = &reveal {
+            for end in 1..identifier.len() {
+                state.open(identifier[..end].to_vec());
+            }
+        }
+
+        let visible = match &self.filter {
+            Some(filter) => flatten_filtered(self.items, &**filter),
+            None => state.flatten(self.items),
+        };
+        let visible = state.advance_reveal(visible, self.reveal_step);
         state.last_biggest_index = visible.len().saturating_sub(1);
         if visible.is_empty() {
             return;
         }
+        // An item is open when its children follow it. This includes ancestors opened by the filter.
+        let is_open = |index: usize| {
+            visible
+                .get(index + 1)
+                .is_some_and(|next| next.depth() > visible[index].depth())
+        };
         let available_height = area.height as usize;
+        let partially_checked = self.partially_checked(state);
+        let heights = visible
+            .iter()
+            .enumerate()
+            .map(|(index, flattened)| {
+                self.item_height(
+                    flattened,
+                    state,
+                    &partially_checked,
+                    area.width,
+                    is_open(index),
+                )
+            })
+            .collect::<Vec<_>>();
 
-        let ensure_index_in_view =
-            if state.ensure_selected_in_view_on_next_render && !state.selected.is_empty() {
-                visible
-                    .iter()
-                    .position(|flattened| flattened.identifier == state.selected)
-            } else {
-                None
-            };
-
-        // Ensure last line is still visible
-        let mut start = state.offset.min(state.last_biggest_index);
+        let ensure_in_view = reveal.as_ref().or_else(|| {
+            (state.ensure_selected_in_view_on_next_render && !state.selected.is_empty())
+                .then_some(&state.selected)
+        });
+        let ensure_index_in_view = ensure_in_view.and_then(|identifier| {
+            visible
+                .iter()
+                .position(|flattened| flattened.identifier == *identifier)
+        });
 
-        if let Some(ensure_index_in_view) = ensure_index_in_view {
-            start = start.min(ensure_index_in_view);
-        }
+        // Margin around the selection, shrunk so both sides fit into the area
+        let scrolloff = (self.scrolloff as usize).min(available_height.saturating_sub(1) / 2);
+        // First and last (exclusive) item to be rendered into the given height together with their height
+        let window = |available_height: usize| {
+            // Scroll at most so far that the last page is still completely filled
+            let mut max_offset = visible.len();
+            let mut last_page_height = 0;
+            while max_offset > 0 && last_page_height + heights[max_offset - 1] <= available_height {
+                max_offset -= 1;
+                last_page_height += heights[max_offset];
+            }
+            let mut start = state
+                .offset
+                .saturating_add(self.viewport_offset)
+                .min(max_offset)
+                .min(visible.len() - 1);
 
-        let mut end = start;
-        let mut height = 0;
-        for item_height in visible
-            .iter()
-            .skip(start)
-            .map(|flattened| flattened.item.height())
-        {
-            if height + item_height > available_height {
-                break;
+            if let Some(ensure_index_in_view) = ensure_index_in_view {
+                start = start.min(ensure_index_in_view.saturating_sub(scrolloff));
             }
-            height += item_height;
-            end += 1;
-        }
 
-        if let Some(ensure_index_in_view) = ensure_index_in_view {
-            while ensure_index_in_view >= end {
-                height += visible[end].item.height();
+            let mut end = start;
+            let mut height = 0;
+            for item_height in heights.iter().skip(start) {
+                // An item taller than the area is still shown (cut off) when it's the first one
+                if height + item_height > available_height && end > start {
+                    break;
+                }
+                height += item_height;
                 end += 1;
-                while height > available_height {
-                    height = height.saturating_sub(visible[start].item.height());
-                    start += 1;
+            }
+
+            if let Some(ensure_index_in_view) = ensure_index_in_view {
+                let ensure_end = (ensure_index_in_view + scrolloff + 1).min(visible.len());
+                while ensure_end > end {
+                    height += heights[end];
+                    end += 1;
+                    // Keep at least the item to be shown even when it's taller than the area
+                    while height > available_height
+                        && start + 1 < end
+                        && start < ensure_index_in_view
+                    {
+                        height = height.saturating_sub(heights[start]);
+                        start += 1;
+                    }
                 }
             }
+            (start, end, height)
+        };
+        // Parent of the first rendered item when it scrolled out of view
+        let sticky_parent = |start: usize| {
+            let depth = visible[start].depth().checked_sub(1)?;
+            visible[..start]
+                .iter()
+                .rposition(|flattened| flattened.depth() == depth)
+        };
+
+        let (mut start, mut end, mut height) = window(available_height);
+        let mut sticky = None;
+        if self.sticky_ancestors && available_height > 1 && sticky_parent(start).is_some() {
+            // The sticky row takes one line so the items have to fit into the rest
+            let (sticky_start, sticky_end, sticky_height) = window(available_height - 1);
+            if let Some(parent) = sticky_parent(sticky_start) {
+                (start, end, height) = (sticky_start, sticky_end, sticky_height);
+                sticky = Some(parent);
+            }
         }
 
-        state.offset = start;
+        if self.viewport_offset == 0 || ensure_index_in_view.is_some() {
+            state.offset = start.saturating_sub(self.viewport_offset);
+        }
         state.ensure_selected_in_view_on_next_render = false;
 
-        if let Some(scrollbar) = self.scrollbar {
+        // The longest label of the rendered items keeps at least its last column visible.
+        // Wrapped labels are shown completely and their heights rely on that.
+        let widest_label = if self.wrap {
+            0
+        } else {
+            (start..end)
+                .map(|index| self.label(visible[index].item, is_open(index)).width())
+                .max()
+                .unwrap_or_default()
+        };
+        state.last_max_horizontal_scroll = widest_label.saturating_sub(1);
+        state.horizontal_scroll = state
+            .horizontal_scroll
+            .min(state.last_max_horizontal_scroll);
+
+        if let Some(scrollbar) = self.scrollbar.take() {
             let mut scrollbar_state = ScrollbarState::new(visible.len().saturating_sub(height))
                 .position(start)
                 .viewport_content_length(height);
-            let scrollbar_area = Rect {
-                // Inner height to be exactly as the content
-                y: area.y,
-                height: area.height,
-                // Outer width to stay on the right border
-                x: full_area.x,
-                width: full_area.width,
-            };
             scrollbar.render(scrollbar_area, buf, &mut scrollbar_state);
         }
 
-        let blank_symbol = " ".repeat(self.highlight_symbol.width());
+        let highlight_width = self.highlight_symbol_width();
+        let blank_symbol = " ".repeat(highlight_width);
+        let node_symbol_width = self.node_symbol_width();
+        let symbol_padding = " ".repeat(node_symbol_width);
+        let highlight_style = if self.focused {
+            self.highlight_style
+        } else {
+            self.inactive_style
+        };
+        let guide_glyphs = self.guide_lines.map(|guides| guides.glyphs(&visible));
 
-        let mut current_height = 0;
         let has_selection = !state.selected.is_empty();
+        let reserve_highlight = self.reserves_highlight_symbol(state);
+        // Index and area of every row to draw, starting with the sticky parent on the top line
+        let mut rows = Vec::with_capacity(end - start + 1);
+        let mut area = area;
+        if let Some(parent) = sticky {
+            rows.push((parent, Rect { height: 1, ..area }));
+            area.y += 1;
+            area.height -= 1;
+        }
+        let mut current_height = 0;
         #[allow(clippy::cast_possible_truncation)]
-        for flattened in visible.iter().skip(state.offset).take(end - start) {
-            let Flattened { identifier, item } = flattened;
-
-            let x = area.x;
-            let y = area.y + current_height;
-            let height = item.height() as u16;
+        for (index, item_height) in heights.iter().enumerate().skip(start).take(end - start) {
+            let height = (*item_height as u16).min(area.height.saturating_sub(current_height));
+            let y = if self.start_corner.is_bottom() {
+                area.bottom().saturating_sub(current_height + height)
+            } else {
+                area.y + current_height
+            };
             current_height += height;
+            rows.push((
+                index,
+                Rect {
+                    x: area.x,
+                    y,
+                    width: area.width,
+                    height,
+                },
+            ));
+        }
 
-            let area = Rect {
-                x,
-                y,
-                width: area.width,
-                height,
-            };
+        #[allow(clippy::cast_possible_truncation)]
+        for (index, area) in rows {
+            let flattened = &visible[index];
+            let Flattened {
+                identifier, item, ..
+            } = flattened;
+            let y = area.y;
+
+            if let Some((even, odd)) = self.row_styles {
+                buf.set_style(area, if index % 2 == 0 { even } else { odd });
+            }
+            if state.selected.len() > identifier.len() && state.selected.starts_with(identifier) {
+                buf.set_style(area, self.lineage_style);
+            }
+            if let Some(dim_style) = self.dim_unrelated {
+                let is_related = state.selected.starts_with(identifier)
+                    || identifier.starts_with(&state.selected);
+                if has_selection && !is_related {
+                    buf.set_style(area, dim_style);
+                }
+            }
+            buf.set_style(area, item.style);
 
-            let text = item.content.to_text();
+            let text = self.label(item, is_open(index));
             let item_style = text.style;
 
+            let mut cursor = RowCursor::new(area, self.is_mirrored());
             let is_selected = state.selected == *identifier;
-            let after_highlight_symbol_x = if has_selection {
-                let symbol = if is_selected {
-                    self.highlight_symbol
+            if reserve_highlight {
+                if is_selected && self.focused {
+                    let symbol = self.highlight_symbol_for(item, is_open(index));
+                    cursor.draw(buf, symbol, item_style);
+                    // Pad narrower symbols so the items do not shift
+                    let padding = highlight_width.saturating_sub(symbol.width());
+                    cursor.draw(buf, &blank_symbol[..padding], item_style);
                 } else {
-                    &blank_symbol
-                };
-                let (x, _) = buf.set_stringn(x, y, symbol, area.width as usize, item_style);
-                x
-            } else {
-                x
-            };
+                    cursor.draw(buf, &blank_symbol, item_style);
+                }
+                // Continuation lines of multi-line items get a blank of the same width
+                let highlight_columns = cursor.column();
+                for line_y in y + 1..area.bottom() {
+                    buf.set_stringn(
+                        cursor.x_of(0, highlight_columns),
+                        line_y,
+                        &blank_symbol,
+                        highlight_columns as usize,
+                        item_style,
+                    );
+                }
+            }
 
-            let after_depth_x = {
-                let indent_width = flattened.depth() * 2;
-                let (after_indent_x, _) = buf.set_stringn(
-                    after_highlight_symbol_x,
-                    y,
-                    " ".repeat(indent_width),
-                    indent_width,
-                    item_style,
-                );
-                let symbol = if item.children.is_empty() {
-                    self.node_no_children_symbol
-                } else if state.opened.contains(identifier.as_slice()) {
-                    self.node_open_symbol
+            let indent_start = cursor.column();
+            let indent_width =
+                (flattened.depth() * self.indent as usize).min(cursor.remaining() as usize) as u16;
+            cursor.draw(buf, &" ".repeat(indent_width as usize), item_style);
+            // Guides which keep their style when the item is highlighted
+            let mut guide_areas = Vec::new();
+            for level in 0..flattened.depth() {
+                let column =
+                    indent_start + (level * self.indent as usize).min(indent_width as usize) as u16;
+                let width = (indent_start + indent_width)
+                    .saturating_sub(column)
+                    .min(self.indent);
+                if width == 0 {
+                    break;
+                }
+                let x = cursor.x_of(column, width);
+                if let (Some(guides), Some(guide_glyphs)) = (self.guide_lines, &guide_glyphs) {
+                    if let Some(glyph) = guide_glyphs[index].get(level) {
+                        let guide_style = item_style.patch(guides.style).patch(self.guide_style);
+                        let mut glyph = Cow::Borrowed(*glyph);
+                        if self.is_mirrored() {
+                            glyph = Cow::Owned(GuideStyle::mirror(&glyph));
+                        }
+                        if self.start_corner.is_bottom() {
+                            // The parent is below its children so the branches point downwards
+                            glyph = Cow::Owned(GuideStyle::flip(&glyph));
+                        }
+                        let glyph_x = if self.is_mirrored() {
+                            // Right aligned within the level so the line stays next to the children
+                            x + width.saturating_sub(glyph.width() as u16)
+                        } else {
+                            x
+                        };
+                        let (end, _) =
+                            buf.set_stringn(glyph_x, y, glyph, width as usize, guide_style);
+                        let glyph_area = Rect::new(glyph_x, y, end.saturating_sub(glyph_x), 1);
+                        if !self.guides_follow_highlight {
+                            guide_areas.push(glyph_area);
+                        }
+                    }
+                }
+                if !self.depth_styles.is_empty() {
+                    let style = self.depth_styles[level % self.depth_styles.len()];
+                    buf.set_style(Rect { x, width, ..area }, style);
+                }
+            }
+            let guide_cells = guide_areas
+                .iter()
+                .flat_map(|area| area.positions())
+                .map(|position| (position, buf[position].clone()))
+                .collect::<Vec<_>>();
+
+            let symbol_range = if item.is_leaf() && !self.align_leaves {
+                None
+            } else {
+                let symbol = self.node_symbol(item, is_open(index));
+                // Pad narrower symbols so the labels do not shift when opening or closing
+                let symbol_padding =
+                    &symbol_padding[..node_symbol_width.saturating_sub(symbol.width())];
+                let symbol_columns = if matches!(self.symbol_alignment, Alignment::Right) {
+                    let columns = cursor.draw_end(buf, symbol, item_style);
+                    cursor.draw_end(buf, symbol_padding, item_style);
+                    columns
                 } else {
-                    self.node_closed_symbol
+                    let columns = cursor.draw(buf, symbol, item_style);
+                    cursor.draw(buf, symbol_padding, item_style);
+                    columns
                 };
-                let max_width = area.width.saturating_sub(after_indent_x - x);
-                let (x, _) =
-                    buf.set_stringn(after_indent_x, y, symbol, max_width as usize, item_style);
-                x
+                item.has_children().then_some(symbol_columns)
             };
 
-            let text_area = Rect {
-                x: after_depth_x,
-                width: area.width.saturating_sub(after_depth_x - x),
-                ..area
-            };
-            text.render(text_area, buf);
+            if let Some(icon) = item.icon {
+                cursor.draw(buf, icon, item_style.patch(self.icon_style));
+            }
+
+            if let Some(prefix) = &self.prefix {
+                let prefix = prefix(item, flattened.depth());
+                cursor.draw(buf, &prefix.content, item_style.patch(prefix.style));
+            }
+
+            if let Some(checkbox) = self.checkbox_symbol(flattened, state, &partially_checked) {
+                cursor.draw(buf, checkbox, item_style);
+            }
+
+            let text_area = cursor.rest();
+            if let Some(label_base_style) = &self.label_base_style {
+                // The own style of the item stays on top
+                let style = label_base_style(flattened.depth()).patch(item.style);
+                buf.set_style(text_area, style);
+            }
+            let is_highlighted = is_selected || state.multi_selected.contains(identifier);
+            let highlight_area = if self.highlight_row { area } else { text_area };
+            let has_match_query = !self.match_query.is_empty();
+            let mut text = text;
+            if self.is_mirrored() && text.alignment.is_none() {
+                text.alignment = Some(Alignment::Right);
+            }
+            if has_match_query {
+                if is_highlighted {
+                    // Highlight before the text so the match style can be patched over the highlight
+                    buf.set_style(highlight_area, highlight_style);
+                    text = highlighted_text(text, highlight_style);
+                }
+                text.lines = text
+                    .lines
+                    .into_iter()
+                    .map(|line| {
+                        line::highlight_first(line, self.match_query, self.highlight_match_style)
+                    })
+                    .collect();
+            }
+            if state.horizontal_scroll > 0 || self.wrap {
+                let lines = text
+                    .lines
+                    .into_iter()
+                    .map(|line| line::skip_columns(line, state.horizontal_scroll))
+                    .flat_map(|line| {
+                        if self.wrap {
+                            line::wrap(line, text_area.width as usize)
+                        } else {
+                            vec![line]
+                        }
+                    })
+                    .collect();
+                Text { lines, ..text }.render(text_area, buf);
+            } else {
+                text.render(text_area, buf);
+            }
+
+            if is_highlighted && !has_match_query {
+                buf.set_style(highlight_area, highlight_style);
+            }
+            if is_highlighted {
+                for (position, cell) in guide_cells {
+                    buf[position] = cell;
+                }
+            }
 
-            if is_selected {
-                buf.set_style(area, self.highlight_style);
+            if sticky == Some(index) {
+                buf.set_style(area, self.sticky_style);
             }
 
-            state
-                .last_rendered_identifiers
-                .push((area.y, identifier.clone()));
+            state.last_rendered.push(RenderedItem {
+                identifier: identifier.clone(),
+                area,
+                symbol: symbol_range,
+            });
         }
+        state.last_heights = heights;
+        state.last_selectable = visible
+            .iter()
+            .map(|flattened| flattened.item.selectable)
+            .collect();
+        state.last_has_children = visible
+            .iter()
+            .map(|flattened| flattened.item.has_children())
+            .collect();
+        state.last_indices = visible
+            .iter()
+            .enumerate()
+            .map(|(index, flattened)| (flattened.identifier.clone(), index))
+            .collect();
         state.last_identifiers = visible
             .into_iter()
             .map(|flattened| flattened.identifier)
@@ -334,7 +1389,32 @@ where
     }
 }
 
-impl<'a, T> Widget for Tree<'a, T>
+/// Patch the `style` onto every part of the `text` like it would be when set onto the buffer after rendering the `text`.
+fn highlighted_text(text: Text<'_>, style: Style) -> Text<'_> {
+    let lines = text
+        .lines
+        .into_iter()
+        .map(|line| {
+            let spans = line
+                .spans
+                .into_iter()
+                .map(|span| span.patch_style(style))
+                .collect();
+            ratatui::text::Line {
+                spans,
+                style: line.style.patch(style),
+                ..line
+            }
+        })
+        .collect();
+    Text {
+        lines,
+        style: text.style.patch(style),
+        ..text
+    }
+}
+
+impl<T> Widget for Tree<'_, T>
 where
     T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
 {
@@ -347,6 +1427,7 @@ where
 #[cfg(test)]
 mod render_tests {
     use super::*;
+    use ratatui::style::Modifier;
     use std::hash::{DefaultHasher, Hasher};
 
     #[must_use]
@@ -354,6 +1435,15 @@ mod render_tests {
     fn render(width: u16, height: u16, state: &mut TreeState) -> Buffer {
         let items = TreeItem::example();
         let tree = Tree::new(&items).unwrap();
+        render_tree(tree, width, height, state)
+    }
+
+    #[must_use]
+    #[track_caller]
+    fn render_tree<T>(tree: Tree<T>, width: u16, height: u16, state: &mut TreeState) -> Buffer
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
         let area = Rect::new(0, 0, width, height);
         let mut buffer = Buffer::empty(area);
         StatefulWidget::render(tree, area, &mut buffer, state);
@@ -368,6 +1458,38 @@ mod render_tests {
         _ = render(10, 10, &mut TreeState::default());
     }
 
+    #[test]
+    fn one_column_inner_area_does_not_panic() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![
+            items[1].identifier(),
+            items[1].children()[1].identifier(),
+        ]);
+        state.select(vec![items[1].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .block(Block::bordered())
+            .highlight_symbol("\u{27a4}\u{27a4} ")
+            .node_closed_symbol("\u{1f4c1} ")
+            .node_open_symbol("\u{1f4c2} ")
+            .guide_lines(GuideStyle::default())
+            .closed_child_count(true);
+        let buffer = render_tree(tree.clone(), 3, 6, &mut state);
+        let expected = Buffer::with_lines(["┌─┐", "│ │", "│➤│", "│ │", "│ │", "└─┘"]);
+        assert_eq!(buffer, expected);
+
+        _ = render_tree(
+            tree.clone().start_corner(Corner::BottomRight),
+            3,
+            6,
+            &mut state,
+        );
+        _ = render_tree(tree.clone().wrap(true), 3, 6, &mut state);
+        _ = render_tree(tree.symbol_alignment(Alignment::Right), 3, 6, &mut state);
+    }
+
     #[test]
     fn nothing_open() {
         let buffer = render(10, 4, &mut TreeState::default());
@@ -381,6 +1503,332 @@ mod render_tests {
         assert_eq!(buffer, expected);
     }
 
+    #[test]
+    fn symbol_alignment_right() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .symbol_alignment(Alignment::Right);
+        let buffer = render_tree(tree.clone(), 12, 4, &mut state);
+        let expected = Buffer::with_lines([
+            "Alfa        ",
+            "Bravo     ▼ ",
+            "  Charlie   ",
+            "  Delta   ▶ ",
+        ]);
+        assert_eq!(buffer, expected);
+
+        // The label is cut off before the symbol
+        let buffer = render_tree(tree, 6, 2, &mut state);
+        let expected = Buffer::with_lines(["Alfa  ", "Brav▼ "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn highlight_symbol_open_and_closed() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">")
+            .highlight_symbol_open("-> ")
+            .highlight_symbol_closed("+> ");
+
+        state.select(vec![items[1].identifier()]);
+        let buffer = render_tree(tree.clone(), 14, 3, &mut state);
+        let expected = Buffer::with_lines(["     Alfa     ", "-> ▼ Bravo    ", "       Charlie"]);
+        assert_eq!(buffer, expected);
+
+        state.close(&[items[1].identifier()]);
+        let buffer = render_tree(tree.clone(), 14, 3, &mut state);
+        let expected = Buffer::with_lines(["     Alfa     ", "+> ▶ Bravo    ", "     Hotel    "]);
+        assert_eq!(buffer, expected);
+
+        state.select(vec![items[0].identifier()]);
+        let buffer = render_tree(tree, 14, 3, &mut state);
+        let expected = Buffer::with_lines([">    Alfa     ", "   ▶ Bravo    ", "     Hotel    "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn symbols_are_padded_to_the_widest() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.select(vec![items[1].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">>> ")
+            .node_open_symbol("-")
+            .node_closed_symbol("+ ")
+            .node_no_children_symbol("");
+        let buffer = render_tree(tree, 16, 5, &mut state);
+        let expected = Buffer::with_lines([
+            "      Alfa      ",
+            ">>> - Bravo     ",
+            "        Charlie ",
+            "      + Delta   ",
+            "        Golf    ",
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn sticky_ancestors_keep_the_parent_visible() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![
+            items[1].identifier(),
+            items[1].children()[1].identifier(),
+        ]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .sticky_ancestors(true)
+            .sticky_style(Style::new().add_modifier(Modifier::BOLD));
+
+        // The parent is visible itself so nothing is sticky
+        state.offset = 1;
+        let buffer = render_tree(tree.clone(), 12, 3, &mut state);
+        let expected = Buffer::with_lines(["▼ Bravo     ", "    Charlie ", "  ▼ Delta   "]);
+        assert_eq!(buffer, expected);
+
+        state.offset = 4;
+        let buffer = render_tree(tree, 12, 3, &mut state);
+        let mut expected = Buffer::with_lines(["  ▼ Delta   ", "      Echo  ", "      Foxtro"]);
+        expected.set_style(Rect::new(0, 0, 12, 1), Modifier::BOLD);
+        assert_eq!(buffer, expected);
+        assert_eq!(state.offset, 4);
+        assert_eq!(
+            state.rendered_at(ratatui::layout::Position::new(3, 0)),
+            Some([items[1].identifier(), items[1].children()[1].identifier()].as_slice())
+        );
+    }
+
+    #[test]
+    fn sticky_parent_looks_like_its_row() {
+        use ratatui::style::Color;
+        let items = [TreeItem::new(
+            "Parent",
+            vec![
+                TreeItem::new_leaf("a"),
+                TreeItem::new_leaf("b"),
+                TreeItem::new_leaf("c"),
+            ],
+        )
+        .unwrap()
+        .style(Style::new().fg(Color::Red))];
+        let mut state = TreeState::default();
+        state.open(vec![items[0].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .sticky_ancestors(true)
+            .symbol_alignment(Alignment::Right)
+            .align_leaves(false);
+
+        let scrolled_in = render_tree(tree.clone(), 10, 4, &mut state);
+        state.offset = 2;
+        let sticky = render_tree(tree, 10, 3, &mut state);
+        assert_eq!(sticky.content[..10], scrolled_in.content[..10]);
+        let mut expected = Buffer::with_lines(["Parent  ▼ ", "  b       ", "  c       "]);
+        expected.set_style(Rect::new(0, 0, 10, 1), Style::new().fg(Color::Red));
+        assert_eq!(sticky, expected);
+    }
+
+    #[test]
+    fn align_leaves() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items).unwrap();
+
+        let buffer = render_tree(tree.clone(), 10, 4, &mut state);
+        let expected = Buffer::with_lines(["  Alfa    ", "▼ Bravo   ", "    Charli", "  ▶ Delta "]);
+        assert_eq!(buffer, expected);
+
+        let buffer = render_tree(tree.align_leaves(false), 10, 4, &mut state);
+        let expected = Buffer::with_lines(["Alfa      ", "▼ Bravo   ", "  Charlie ", "  ▶ Delta "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn lineage_style_tints_the_ancestors() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let echo = items[1].children()[1].children()[0].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, delta]);
+        state.select(vec![bravo, delta, echo]);
+        let lineage_style = Style::new().bg(ratatui::style::Color::DarkGray);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .lineage_style(lineage_style)
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD));
+        let buffer = render_tree(tree, 12, 6, &mut state);
+        let mut expected = Buffer::with_lines([
+            "  Alfa      ",
+            "▼ Bravo     ",
+            "    Charlie ",
+            "  ▼ Delta   ",
+            "      Echo  ",
+            "      Foxtro",
+        ]);
+        expected.set_style(Rect::new(0, 1, 12, 1), lineage_style);
+        expected.set_style(Rect::new(0, 3, 12, 1), lineage_style);
+        expected.set_style(Rect::new(0, 4, 12, 1), Modifier::BOLD);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn dim_unrelated_rows_outside_the_selected_branch() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, delta]);
+        let dim = Style::new().add_modifier(Modifier::DIM);
+        let tree = Tree::new(&items).unwrap().dim_unrelated(dim);
+
+        let buffer = render_tree(tree.clone(), 12, 3, &mut state);
+        let expected = Buffer::with_lines(["  Alfa      ", "▼ Bravo     ", "    Charlie "]);
+        assert_eq!(buffer, expected);
+
+        state.select(vec![bravo, delta]);
+        let buffer = render_tree(tree, 12, 8, &mut state);
+        let mut expected = Buffer::with_lines([
+            "  Alfa      ",
+            "▼ Bravo     ",
+            "    Charlie ",
+            "  ▼ Delta   ",
+            "      Echo  ",
+            "      Foxtro",
+            "    Golf    ",
+            "  Hotel     ",
+        ]);
+        for y in [0, 2, 6, 7] {
+            expected.set_style(Rect::new(0, y, 12, 1), dim);
+        }
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn highlight_symbol_only_on_the_first_line() {
+        let items = [
+            TreeItem::new_leaf("A"),
+            TreeItem::new_leaf("one\ntwo\nthree"),
+        ];
+        let mut state = TreeState::default();
+        state.select(vec![items[1].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">> ")
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD));
+        let buffer = render_tree(tree, 10, 4, &mut state);
+        let mut expected =
+            Buffer::with_lines(["     A    ", ">>   one  ", "     two  ", "     three"]);
+        expected.set_style(Rect::new(0, 1, 10, 3), Modifier::BOLD);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn highlight_spacing() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        let tree = Tree::new(&items).unwrap().highlight_symbol(">> ");
+        let render_spacing = |spacing: HighlightSpacing, state: &mut TreeState| {
+            render_tree(tree.clone().highlight_spacing(spacing), 10, 2, state)
+        };
+
+        let nothing_reserved = Buffer::with_lines(["  Alfa    ", "▶ Bravo   "]);
+        let reserved = Buffer::with_lines(["     Alfa ", "   ▶ Bravo"]);
+        assert_eq!(
+            render_spacing(HighlightSpacing::WhenSelected, &mut state),
+            nothing_reserved
+        );
+        assert_eq!(
+            render_spacing(HighlightSpacing::Always, &mut state),
+            reserved
+        );
+        assert_eq!(
+            render_spacing(HighlightSpacing::Never, &mut state),
+            nothing_reserved
+        );
+
+        state.select(vec![items[0].identifier()]);
+        let selected = Buffer::with_lines([">>   Alfa ", "   ▶ Bravo"]);
+        assert_eq!(
+            render_spacing(HighlightSpacing::WhenSelected, &mut state),
+            selected
+        );
+        assert_eq!(
+            render_spacing(HighlightSpacing::Always, &mut state),
+            selected
+        );
+        assert_eq!(
+            render_spacing(HighlightSpacing::Never, &mut state),
+            nothing_reserved
+        );
+    }
+
+    #[test]
+    fn block_with_count_appends_to_the_title() {
+        let items = TreeItem::example();
+        let tree = Tree::new(&items)
+            .unwrap()
+            .block_with_count(Block::bordered().title("Items"), |count| {
+                format!("({count})")
+            });
+        let buffer = render_tree(tree.clone(), 14, 3, &mut TreeState::default());
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(["┌Items─(8)───┐", "│  Alfa      │", "└────────────┘"])
+        );
+
+        let fewer = [TreeItem::new_leaf("Alfa")];
+        let tree = tree.items(&fewer).unwrap();
+        let buffer = render_tree(tree, 14, 3, &mut TreeState::default());
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(["┌Items─(1)───┐", "│  Alfa      │", "└────────────┘"])
+        );
+    }
+
+    #[test]
+    fn label_base_style_is_below_the_label_and_highlight() {
+        use ratatui::style::Color;
+        let items = [
+            TreeItem::new_leaf("own"),
+            TreeItem::new_leaf("red").style(Style::new().fg(Color::Red)),
+            TreeItem::new("sel", vec![TreeItem::new_leaf("kid")]).unwrap(),
+        ];
+        let mut state = TreeState::default();
+        state.open(vec![items[2].identifier()]);
+        state.select(vec![items[2].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .label_base_style_fn(|depth| {
+                Style::new().fg(if depth == 0 {
+                    Color::Blue
+                } else {
+                    Color::Green
+                })
+            })
+            .highlight_style(Style::new().fg(Color::Yellow));
+        let buffer = render_tree(tree, 9, 4, &mut state);
+        let mut expected = Buffer::with_lines(["  own    ", "  red    ", "▼ sel    ", "    kid  "]);
+        expected.set_style(Rect::new(2, 0, 7, 1), Color::Blue);
+        expected.set_style(Rect::new(0, 1, 9, 1), Color::Red);
+        expected.set_style(Rect::new(0, 2, 9, 1), Color::Yellow);
+        expected.set_style(Rect::new(4, 3, 5, 1), Color::Green);
+        assert_eq!(buffer, expected);
+    }
+
     #[test]
     fn depth_one() {
         let mut state = TreeState::default();
@@ -424,4 +1872,817 @@ mod render_tests {
         ]);
         assert_eq!(buffer, expected);
     }
+
+    #[test]
+    fn custom_indent() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![
+            items[1].identifier(),
+            items[1].children()[1].identifier(),
+        ]);
+        let tree = Tree::new(&items).unwrap().indent(4);
+        let buffer = render_tree(tree, 17, 9, &mut state);
+        let expected = Buffer::with_lines([
+            "  Alfa           ",
+            "▼ Bravo          ",
+            "      Charlie    ",
+            "    ▼ Delta      ",
+            "          Echo   ",
+            "          Foxtrot",
+            "      Golf       ",
+            "  Hotel          ",
+            "                 ",
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn indent_wider_than_area_does_not_panic() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items).unwrap().indent(u16::MAX);
+        let buffer = render_tree(tree, 5, 3, &mut state);
+        let expected = Buffer::with_lines(["  Alf", "▼ Bra", "     "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn guide_lines() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![
+            items[1].identifier(),
+            items[1].children()[1].identifier(),
+        ]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .guide_lines(GuideStyle::default());
+        let buffer = render_tree(tree, 15, 9, &mut state);
+        let expected = Buffer::with_lines([
+            "  Alfa         ",
+            "▼ Bravo        ",
+            "├─  Charlie    ",
+            "├─▼ Delta      ",
+            "│ ├─  Echo     ",
+            "│ └─  Foxtrot  ",
+            "└─  Golf       ",
+            "  Hotel        ",
+            "               ",
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn guide_style_is_kept_on_highlighted_rows() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.select(vec![
+            items[1].identifier(),
+            items[1].children()[0].identifier(),
+        ]);
+        let guide_style = Style::new().fg(ratatui::style::Color::DarkGray);
+        let highlight_style = Style::new().fg(ratatui::style::Color::Yellow);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .guide_lines(GuideStyle::ASCII)
+            .guide_style(guide_style)
+            .highlight_style(highlight_style);
+
+        let buffer = render_tree(tree.clone(), 12, 3, &mut state);
+        let mut expected = Buffer::with_lines(["  Alfa      ", "▼ Bravo     ", "|-  Charlie "]);
+        expected.set_style(Rect::new(0, 2, 12, 1), highlight_style);
+        assert_eq!(buffer, expected);
+
+        let buffer = render_tree(tree.guides_follow_highlight(false), 12, 3, &mut state);
+        expected.set_style(Rect::new(0, 2, 2, 1), guide_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn guide_lines_skip_levels_without_following_siblings() {
+        let b = TreeItem::new("B", vec![TreeItem::new_leaf("C")]).unwrap();
+        let b_identifier = b.identifier();
+        let root = TreeItem::new("Root", vec![TreeItem::new_leaf("A"), b]).unwrap();
+        let root_identifier = root.identifier();
+        let items = [root];
+        let mut state = TreeState::default();
+        state.open(vec![root_identifier]);
+        state.open(vec![root_identifier, b_identifier]);
+        let tree = Tree::new(&items).unwrap().guide_lines(GuideStyle::ASCII);
+        let buffer = render_tree(tree, 8, 4, &mut state);
+        let expected = Buffer::with_lines(["▼ Root  ", "|-  A   ", "`-▼ B   ", "  `-  C "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn multi_selected_items_are_highlighted() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.select(vec![items[0].identifier()]);
+        state.toggle_selected_multi();
+        state.select(vec![items[2].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">")
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD));
+        let buffer = render_tree(tree, 8, 3, &mut state);
+        let mut expected = Buffer::with_lines(["   Alfa ", " ▶ Bravo", ">  Hotel"]);
+        expected.set_style(
+            Rect::new(0, 0, 8, 1),
+            Style::new().add_modifier(Modifier::BOLD),
+        );
+        expected.set_style(
+            Rect::new(0, 2, 8, 1),
+            Style::new().add_modifier(Modifier::BOLD),
+        );
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn highlight_row_covers_all_lines_of_the_item() {
+        let items = [
+            TreeItem::new_leaf("Line 1\nLine 2"),
+            TreeItem::new_leaf("Other"),
+        ];
+        let highlight_style = Style::new().add_modifier(Modifier::BOLD);
+        let mut state = TreeState::default();
+        state.select(vec![items[0].identifier()]);
+
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">")
+            .highlight_style(highlight_style);
+        let buffer = render_tree(tree, 10, 3, &mut state);
+        let mut expected = Buffer::with_lines([">  Line 1 ", "   Line 2 ", "   Other  "]);
+        expected.set_style(Rect::new(0, 0, 10, 2), highlight_style);
+        assert_eq!(buffer, expected);
+
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">")
+            .highlight_style(highlight_style)
+            .highlight_row(false);
+        let buffer = render_tree(tree, 10, 3, &mut state);
+        let mut expected = Buffer::with_lines([">  Line 1 ", "   Line 2 ", "   Other  "]);
+        expected.set_style(Rect::new(3, 0, 7, 2), highlight_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn item_style_is_applied_to_the_row() {
+        let item_style = Style::new().bg(ratatui::style::Color::Red);
+        let items = [
+            TreeItem::new_leaf("Alfa").style(item_style),
+            TreeItem::new_leaf("Bravo"),
+        ];
+        let tree = Tree::new(&items)
+            .unwrap()
+            .style(Style::new().add_modifier(Modifier::ITALIC));
+        let buffer = render_tree(tree, 8, 2, &mut TreeState::default());
+        let mut expected = Buffer::with_lines(["  Alfa  ", "  Bravo "]);
+        expected.set_style(expected.area, Style::new().add_modifier(Modifier::ITALIC));
+        expected.set_style(Rect::new(0, 0, 8, 1), item_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn scrollbar_takes_the_right_column() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let scrollbar = Scrollbar::new(ratatui::widgets::ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(Some("|"))
+            .thumb_symbol("#");
+        let tree = Tree::new(&items)
+            .unwrap()
+            .block(Block::bordered())
+            .scrollbar(scrollbar);
+        let buffer = render_tree(tree, 10, 5, &mut state);
+        let expected = Buffer::with_lines([
+            "┌────────┐",
+            "│  Alfa #│",
+            "│▼ Bravo#│",
+            "│    Cha|│",
+            "└────────┘",
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn horizontal_scroll_keeps_symbols_pinned() {
+        let mut state = TreeState::default();
+        _ = render(10, 4, &mut state);
+        state.scroll_right(3);
+        let buffer = render(10, 4, &mut state);
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "  a       ",
+            "▶ vo      ",
+            "  el      ",
+            "          ",
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn horizontal_scroll_is_clamped_to_the_widest_label() {
+        let mut state = TreeState::default();
+        _ = render(10, 4, &mut state);
+        state.scroll_right(100);
+        let buffer = render(10, 4, &mut state);
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "          ",
+            "▶ o       ",
+            "  l       ",
+            "          ",
+        ]);
+        assert_eq!(buffer, expected);
+        assert!(!state.scroll_right(1));
+        assert!(state.scroll_left(10));
+        assert!(!state.scroll_left(1));
+    }
+
+    #[test]
+    fn wrap_long_labels() {
+        let items = [
+            TreeItem::new(
+                "Parent with a long label",
+                vec![TreeItem::new_leaf("Child")],
+            )
+            .unwrap(),
+            TreeItem::new_leaf("Short"),
+        ];
+        let tree = Tree::new(&items).unwrap().wrap(true);
+        let buffer = render_tree(tree, 10, 5, &mut TreeState::default());
+        let expected = Buffer::with_lines([
+            "▶ Parent w",
+            "  ith a lo",
+            "  ng label",
+            "  Short   ",
+            "          ",
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn selected_item_taller_than_the_area_is_cut_off() {
+        let tall = (0..50).map(|line| line.to_string()).collect::<Vec<_>>();
+        let items = [
+            TreeItem::new_leaf("Alfa".to_owned()),
+            TreeItem::new_leaf(tall.join("\n")),
+            TreeItem::new_leaf("Charlie".to_owned()),
+        ];
+        let mut state = TreeState::default();
+        state.select(vec![items[1].identifier()]);
+        let tree = Tree::new(&items).unwrap().block(Block::bordered());
+        let buffer = render_tree(tree, 6, 12, &mut state);
+        let expected = Buffer::with_lines([
+            "┌────┐",
+            "│  0 │",
+            "│  1 │",
+            "│  2 │",
+            "│  3 │",
+            "│  4 │",
+            "│  5 │",
+            "│  6 │",
+            "│  7 │",
+            "│  8 │",
+            "│  9 │",
+            "└────┘",
+        ]);
+        assert_eq!(buffer, expected);
+
+        // Being scrolled onto the tall item shows it even without the selection
+        state.select(Vec::new());
+        assert_eq!(state.offset(), 1);
+        let tree = Tree::new(&items).unwrap().block(Block::bordered());
+        assert_eq!(render_tree(tree, 6, 12, &mut state), expected);
+    }
+
+    #[test]
+    fn offset_is_clamped_when_items_shrink() {
+        let mut state = TreeState::default();
+        let items = TreeItem::example();
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![
+            items[1].identifier(),
+            items[1].children()[1].identifier(),
+        ]);
+        _ = render(10, 3, &mut state);
+        state.scroll_down(100);
+        let buffer = render(10, 3, &mut state);
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "      Foxt",
+            "    Golf  ",
+            "  Hotel   ",
+        ]);
+        assert_eq!(buffer, expected);
+
+        let items = [TreeItem::new_leaf("Alfa"), TreeItem::new_leaf("Bravo")];
+        let tree = Tree::new(&items).unwrap();
+        let buffer = render_tree(tree, 10, 3, &mut state);
+        #[rustfmt::skip]
+        let expected = Buffer::with_lines([
+            "  Alfa    ",
+            "  Bravo   ",
+            "          ",
+        ]);
+        assert_eq!(buffer, expected);
+        assert_eq!(state.offset(), 0);
+    }
+
+    #[test]
+    fn filter_keeps_ancestors_of_matches_open() {
+        let items = TreeItem::example();
+        let tree = Tree::new(&items)
+            .unwrap()
+            .filter(|item| item.content().to_lowercase().contains('e'));
+        let mut state = TreeState::default();
+        let buffer = render_tree(tree, 14, 6, &mut state);
+        let expected = Buffer::with_lines([
+            "▼ Bravo       ",
+            "    Charlie   ",
+            "  ▼ Delta     ",
+            "      Echo    ",
+            "  Hotel       ",
+            "              ",
+        ]);
+        assert_eq!(buffer, expected);
+        assert!(state.opened().is_empty());
+
+        state.select_last();
+        assert_eq!(state.selected(), [items[2].identifier()]);
+    }
+
+    #[test]
+    fn match_query_is_highlighted() {
+        use ratatui::style::Color;
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.select(vec![items[0].identifier()]);
+        let highlight_style = Style::new().bg(Color::Blue).fg(Color::White);
+        let match_style = Style::new().fg(Color::Red);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_style(highlight_style)
+            .match_query("L")
+            .highlight_match_style(match_style);
+        let buffer = render_tree(tree, 8, 3, &mut state);
+        let mut expected = Buffer::with_lines(["  Alfa  ", "▶ Bravo ", "  Hotel "]);
+        expected.set_style(Rect::new(0, 0, 8, 1), highlight_style);
+        expected.set_style(Rect::new(3, 0, 1, 1), match_style);
+        expected.set_style(Rect::new(6, 2, 1, 1), match_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn closed_child_count() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items).unwrap().closed_child_count(true);
+        let buffer = render_tree(tree, 14, 6, &mut state);
+        let expected = Buffer::with_lines([
+            "  Alfa        ",
+            "▼ Bravo       ",
+            "    Charlie   ",
+            "  ▶ Delta (2) ",
+            "    Golf      ",
+            "  Hotel       ",
+        ]);
+        assert_eq!(buffer, expected);
+
+        let tree = Tree::new(&items).unwrap().closed_child_count(true);
+        let buffer = render_tree(tree, 9, 2, &mut TreeState::default());
+        assert_eq!(buffer, Buffer::with_lines(["  Alfa   ", "▶ Bravo (",]));
+    }
+
+    #[test]
+    fn closed_child_count_is_wrapped_with_the_label() {
+        let items = TreeItem::example();
+        let tree = Tree::new(&items)
+            .unwrap()
+            .closed_child_count(true)
+            .wrap(true);
+        let buffer = render_tree(tree, 9, 4, &mut TreeState::default());
+        let expected = Buffer::with_lines(["  Alfa   ", "▶ Bravo (", "  3)     ", "  Hotel  "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn wrap_ignores_horizontal_scroll() {
+        let items = [
+            TreeItem::new_leaf("Alfa with a long label"),
+            TreeItem::new_leaf("Bravo"),
+        ];
+        let mut state = TreeState::default();
+        _ = render_tree(Tree::new(&items).unwrap(), 10, 3, &mut state);
+        assert!(state.scroll_right(8));
+
+        let tree = Tree::new(&items).unwrap().wrap(true);
+        let buffer = render_tree(tree.clone(), 10, 4, &mut state);
+        let expected = Buffer::with_lines(["  Alfa wit", "  h a long", "   label  ", "  Bravo   "]);
+        assert_eq!(buffer, expected);
+        assert_eq!(state.last_heights, [3, 1]);
+        assert!(!state.scroll_right(1));
+
+        let buffer = render_tree(tree, 10, 4, &mut state);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn prefix_is_drawn_between_symbol_and_label() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items).unwrap().prefix(|item, depth| {
+            let letter = if item.is_leaf() { "F" } else { "D" };
+            Span::raw(format!("{letter}{depth} "))
+        });
+        let buffer = render_tree(tree, 12, 4, &mut state);
+        let expected = Buffer::with_lines([
+            "  F0 Alfa   ",
+            "▼ D0 Bravo  ",
+            "    F1 Charl",
+            "  ▶ D1 Delta",
+        ]);
+        assert_eq!(buffer, expected);
+
+        let tree = Tree::new(&items).unwrap().prefix(|_, _| Span::raw("12345"));
+        let buffer = render_tree(tree, 4, 1, &mut TreeState::default());
+        assert_eq!(buffer, Buffer::with_lines(["  12"]));
+    }
+
+    #[test]
+    fn icon_shifts_the_label() {
+        use ratatui::style::Color;
+        let items = [
+            TreeItem::new_leaf("Alfa").icon("📄"),
+            TreeItem::new("Bravo", vec![TreeItem::new_leaf("Charlie")])
+                .unwrap()
+                .icon("D "),
+            TreeItem::new_leaf("Hotel"),
+        ];
+        let icon_style = Style::new().fg(Color::Yellow);
+        let tree = Tree::new(&items).unwrap().icon_style(icon_style);
+        let buffer = render_tree(tree, 10, 3, &mut TreeState::default());
+        let mut expected = Buffer::with_lines(["  📄Alfa  ", "▶ D Bravo ", "  Hotel   "]);
+        expected.set_style(Rect::new(2, 0, 1, 1), icon_style);
+        expected.set_style(Rect::new(2, 1, 2, 1), icon_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn depth_styles_tint_the_indentation() {
+        use ratatui::style::Color;
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, items[1].children()[1].identifier()]);
+        state.select(vec![bravo, items[1].children()[1].identifier()]);
+        let red = Style::new().bg(Color::Red);
+        let blue = Style::new().bg(Color::Blue);
+        let highlight_style = Style::new().add_modifier(Modifier::BOLD);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_style(highlight_style)
+            .depth_styles(vec![red, blue]);
+        let buffer = render_tree(tree, 12, 6, &mut state);
+        let mut expected = Buffer::with_lines([
+            "  Alfa      ",
+            "▼ Bravo     ",
+            "    Charlie ",
+            "  ▼ Delta   ",
+            "      Echo  ",
+            "      Foxtro",
+        ]);
+        expected.set_style(Rect::new(0, 2, 2, 1), red);
+        expected.set_style(Rect::new(0, 3, 2, 1), red);
+        expected.set_style(Rect::new(0, 3, 12, 1), highlight_style);
+        expected.set_style(Rect::new(0, 4, 2, 1), red);
+        expected.set_style(Rect::new(2, 4, 2, 1), blue);
+        expected.set_style(Rect::new(0, 5, 2, 1), red);
+        expected.set_style(Rect::new(2, 5, 2, 1), blue);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn row_styles_alternate() {
+        use ratatui::style::Color;
+        let items = [
+            TreeItem::new_leaf("Alfa"),
+            TreeItem::new_leaf("Bravo\nBravo"),
+            TreeItem::new_leaf("Charlie"),
+            TreeItem::new_leaf("Delta"),
+        ];
+        let even = Style::new().bg(Color::Black);
+        let odd = Style::new().bg(Color::DarkGray);
+        let tree = Tree::new(&items).unwrap().row_styles(even, odd);
+        let mut state = TreeState::default();
+        let buffer = render_tree(tree.clone(), 9, 4, &mut state);
+        let mut expected = Buffer::with_lines(["  Alfa   ", "  Bravo  ", "  Bravo  ", "  Charlie"]);
+        expected.set_style(Rect::new(0, 0, 9, 1), even);
+        expected.set_style(Rect::new(0, 1, 9, 2), odd);
+        expected.set_style(Rect::new(0, 3, 9, 1), even);
+        assert_eq!(buffer, expected);
+
+        state.scroll_down(1);
+        let buffer = render_tree(tree, 9, 4, &mut state);
+        let mut expected = Buffer::with_lines(["  Bravo  ", "  Bravo  ", "  Charlie", "  Delta  "]);
+        expected.set_style(Rect::new(0, 0, 9, 2), odd);
+        expected.set_style(Rect::new(0, 2, 9, 1), even);
+        expected.set_style(Rect::new(0, 3, 9, 1), odd);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn unfocused_uses_inactive_style_without_symbol() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.select(vec![items[1].identifier()]);
+        let highlight_style = Style::new().add_modifier(Modifier::BOLD);
+        let inactive_style = Style::new().add_modifier(Modifier::DIM);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">")
+            .highlight_style(highlight_style)
+            .inactive_style(inactive_style);
+
+        let buffer = render_tree(tree.clone(), 8, 3, &mut state);
+        let mut expected = Buffer::with_lines(["   Alfa ", ">▶ Bravo", "   Hotel"]);
+        expected.set_style(Rect::new(0, 1, 8, 1), highlight_style);
+        assert_eq!(buffer, expected);
+
+        let buffer = render_tree(tree.focused(false), 8, 3, &mut state);
+        let mut expected = Buffer::with_lines(["   Alfa ", " ▶ Bravo", "   Hotel"]);
+        expected.set_style(Rect::new(0, 1, 8, 1), inactive_style);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn start_corners() {
+        let items = TreeItem::example();
+        let render_corner = |corner| {
+            let mut state = TreeState::default();
+            state.open(vec![items[1].identifier()]);
+            state.select(vec![items[1].identifier()]);
+            let tree = Tree::new(&items)
+                .unwrap()
+                .highlight_symbol(">")
+                .guide_lines(GuideStyle::UNICODE)
+                .start_corner(corner);
+            render_tree(tree, 12, 8, &mut state)
+        };
+
+        let expected = Buffer::with_lines([
+            "   Alfa     ",
+            ">▼ Bravo    ",
+            " ├─  Charlie",
+            " ├─▶ Delta  ",
+            " └─  Golf   ",
+            "   Hotel    ",
+            "            ",
+            "            ",
+        ]);
+        assert_eq!(render_corner(Corner::TopLeft), expected);
+
+        let expected = Buffer::with_lines([
+            "     Alfa   ",
+            "    Bravo▼ >",
+            "Charlie  ─┤ ",
+            "  Delta▶ ─┤ ",
+            "   Golf  ─┘ ",
+            "    Hotel   ",
+            "            ",
+            "            ",
+        ]);
+        assert_eq!(render_corner(Corner::TopRight), expected);
+
+        let expected = Buffer::with_lines([
+            "            ",
+            "            ",
+            "    Hotel   ",
+            "   Golf  ─┐ ",
+            "  Delta▶ ─┤ ",
+            "Charlie  ─┤ ",
+            "    Bravo▼ >",
+            "     Alfa   ",
+        ]);
+        assert_eq!(render_corner(Corner::BottomRight), expected);
+
+        let expected = Buffer::with_lines([
+            "            ",
+            "            ",
+            "   Hotel    ",
+            " ┌─  Golf   ",
+            " ├─▶ Delta  ",
+            " ├─  Charlie",
+            ">▼ Bravo    ",
+            "   Alfa     ",
+        ]);
+        assert_eq!(render_corner(Corner::BottomLeft), expected);
+    }
+
+    #[test]
+    fn start_corner_bottom_left_keeps_the_selection_in_view() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, delta]);
+        state.select(vec![
+            bravo,
+            delta,
+            items[1].children()[1].children()[0].identifier(),
+        ]);
+        state.ensure_selected_in_view();
+        let tree = Tree::new(&items)
+            .unwrap()
+            .highlight_symbol(">")
+            .guide_lines(GuideStyle::default())
+            .start_corner(Corner::BottomLeft);
+        let buffer = render_tree(tree, 13, 4, &mut state);
+        let expected = Buffer::with_lines([
+            ">│ ├─  Echo  ",
+            " ├─▼ Delta   ",
+            " ├─  Charlie ",
+            " ▼ Bravo     ",
+        ]);
+        assert_eq!(buffer, expected);
+        assert_eq!(state.offset(), 1);
+    }
+
+    #[test]
+    fn start_corner_right_hit_tests_the_symbol() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        let tree = Tree::new(&items).unwrap().start_corner(Corner::TopRight);
+        _ = render_tree(tree, 12, 3, &mut state);
+        assert_eq!(
+            state.toggle_target_at(ratatui::layout::Position::new(10, 1)),
+            Some(ToggleHit::Symbol)
+        );
+        assert_eq!(
+            state.toggle_target_at(ratatui::layout::Position::new(2, 1)),
+            Some(ToggleHit::Label)
+        );
+    }
+
+    #[test]
+    fn direction_rtl() {
+        let items = [
+            TreeItem::new("שלום", vec![TreeItem::new_leaf("עולם")]).unwrap(),
+            TreeItem::new_leaf("abc"),
+        ];
+        let mut state = TreeState::default();
+        state.open(vec![items[0].identifier()]);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .node_open_symbol(" ▼")
+            .node_no_children_symbol("  ")
+            .direction(TextDirection::Rtl);
+        let buffer = render_tree(tree, 10, 3, &mut state);
+        let expected = Buffer::with_lines(["    שלום ▼", "  עולם    ", "     abc  "]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn reveal_step_shows_opened_children_progressively() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        let visible_after_render = |state: &mut TreeState| {
+            let tree = Tree::new(&items).unwrap().reveal_step(1);
+            _ = render_tree(tree, 10, 10, state);
+            state.scrollbar_state().0
+        };
+        assert_eq!(visible_after_render(&mut state), 3);
+        assert!(!state.is_revealing());
+
+        // Bravo has Charlie, Delta and Golf
+        state.open(vec![bravo]);
+        assert_eq!(visible_after_render(&mut state), 4);
+        assert!(state.is_revealing());
+        assert_eq!(visible_after_render(&mut state), 5);
+        assert_eq!(visible_after_render(&mut state), 6);
+        assert!(!state.is_revealing());
+        assert_eq!(visible_after_render(&mut state), 6);
+
+        // Closing stops revealing, opening again starts over
+        state.close(&[bravo]);
+        state.open(vec![bravo]);
+        state.open(vec![bravo, items[1].children()[1].identifier()]);
+        assert_eq!(visible_after_render(&mut state), 4);
+        assert_eq!(visible_after_render(&mut state), 5);
+        assert_eq!(visible_after_render(&mut state), 6);
+        assert_eq!(visible_after_render(&mut state), 7);
+        assert_eq!(visible_after_render(&mut state), 8);
+        assert!(!state.is_revealing());
+    }
+
+    #[test]
+    fn reveal_step_zero_shows_everything_at_once() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        let tree = Tree::new(&items).unwrap();
+        _ = render_tree(tree, 10, 10, &mut state);
+        assert_eq!(state.scrollbar_state().0, 6);
+        assert!(!state.is_revealing());
+    }
+
+    #[test]
+    fn viewport_offset_shows_another_window_of_the_same_state() {
+        let items = (0..10)
+            .map(|index| TreeItem::new_leaf(index.to_string()))
+            .collect::<Vec<_>>();
+        let mut state = TreeState::default();
+        let render_view = |viewport_offset: usize, state: &mut TreeState| {
+            let tree = Tree::new(&items).unwrap().viewport_offset(viewport_offset);
+            render_tree(tree, 5, 3, state)
+        };
+
+        let top = Buffer::with_lines(["  0  ", "  1  ", "  2  "]);
+        let below = Buffer::with_lines(["  3  ", "  4  ", "  5  "]);
+        assert_eq!(render_view(0, &mut state), top);
+        assert_eq!(render_view(3, &mut state), below);
+        assert_eq!(state.offset(), 0);
+
+        // Clamped to the last page without moving the other view
+        assert_eq!(
+            render_view(20, &mut state),
+            Buffer::with_lines(["  7  ", "  8  ", "  9  "])
+        );
+        assert_eq!(state.offset(), 0);
+        assert_eq!(render_view(0, &mut state), top);
+
+        // Revealing the selection moves the shared offset
+        state.select(vec![items[7].identifier()]);
+        _ = render_view(3, &mut state);
+        assert_eq!(state.offset(), 2);
+        assert_eq!(
+            render_view(0, &mut state),
+            Buffer::with_lines(["  2  ", "  3  ", "  4  "])
+        );
+    }
+
+    #[test]
+    fn scrolloff_keeps_context_around_the_selection() {
+        let items = (0..10)
+            .map(|index| TreeItem::new_leaf(index.to_string()))
+            .collect::<Vec<_>>();
+        let mut state = TreeState::default();
+        let select = |index: usize, state: &mut TreeState| {
+            state.select(vec![items[index].identifier()]);
+            let tree = Tree::new(&items).unwrap().scrolloff(1);
+            _ = render_tree(tree, 5, 5, state);
+            state.offset()
+        };
+
+        // Top edge: no context above the first item
+        assert_eq!(select(0, &mut state), 0);
+        // Middle: moving down keeps one item below
+        assert_eq!(select(3, &mut state), 0);
+        assert_eq!(select(4, &mut state), 1);
+        assert_eq!(select(6, &mut state), 3);
+        // Moving up keeps one item above
+        assert_eq!(select(4, &mut state), 3);
+        assert_eq!(select(3, &mut state), 2);
+        // Bottom edge: no context below the last item
+        assert_eq!(select(9, &mut state), 5);
+        assert_eq!(select(8, &mut state), 5);
+    }
+
+    #[test]
+    fn checkbox_symbols() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.set_checked(&items, &[bravo, items[1].children()[0].identifier()], true);
+        let tree = Tree::new(&items)
+            .unwrap()
+            .checkbox_symbols("[x]", "[ ]", "[-]");
+        let buffer = render_tree(tree, 14, 6, &mut state);
+        let expected = Buffer::with_lines([
+            "  [ ]Alfa     ",
+            "▼ [-]Bravo    ",
+            "    [x]Charlie",
+            "  ▶ [ ]Delta  ",
+            "    [ ]Golf   ",
+            "  [ ]Hotel    ",
+        ]);
+        assert_eq!(buffer, expected);
+    }
 }
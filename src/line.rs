@@ -0,0 +1,198 @@
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthChar;
+
+/// Skip the given amount of columns at the start of the `line` while keeping the styling.
+///
+/// A wide character which is only partially skipped is replaced with spaces.
+pub fn skip_columns(line: Line<'_>, columns: usize) -> Line<'_> {
+    let mut remaining = columns;
+    let spans = line
+        .spans
+        .into_iter()
+        .filter_map(|span| {
+            if remaining == 0 {
+                return Some(span);
+            }
+            let width = span.width();
+            if width <= remaining {
+                remaining -= width;
+                return None;
+            }
+
+            let mut skipped = 0;
+            let mut content = String::new();
+            for character in span.content.chars() {
+                if skipped < remaining {
+                    skipped += character.width().unwrap_or(0);
+                } else {
+                    content.push(character);
+                }
+            }
+            let padding = " ".repeat(skipped - remaining);
+            remaining = 0;
+            Some(Span::styled(padding + &content, span.style))
+        })
+        .collect();
+    Line { spans, ..line }
+}
+
+/// Wrap the `line` into lines which are at most `width` columns wide while keeping the styling.
+///
+/// The line is wrapped after any character, not only on whitespace.
+/// An empty line or a `width` of zero results in the line unchanged.
+pub fn wrap(line: Line<'_>, width: usize) -> Vec<Line<'_>> {
+    if width == 0 || line.width() <= width {
+        return vec![line];
+    }
+
+    let mut lines = Vec::new();
+    let mut current = Vec::new();
+    let mut current_width = 0;
+    for span in &line.spans {
+        let mut content = String::new();
+        for character in span.content.chars() {
+            let character_width = character.width().unwrap_or(0);
+            if current_width + character_width > width && current_width > 0 {
+                if !content.is_empty() {
+                    current.push(Span::styled(std::mem::take(&mut content), span.style));
+                }
+                lines.push(Line {
+                    spans: std::mem::take(&mut current),
+                    ..line.clone()
+                });
+                current_width = 0;
+            }
+            content.push(character);
+            current_width += character_width;
+        }
+        if !content.is_empty() {
+            current.push(Span::styled(content, span.style));
+        }
+    }
+    lines.push(Line {
+        spans: current,
+        ..line
+    });
+    lines
+}
+
+/// Patch the `style` onto the first case-insensitive occurrence of the `query` in the `line`.
+///
+/// Spans are split where the occurrence starts and ends so the rest keeps its styling.
+pub fn highlight_first<'a>(line: Line<'a>, query: &str, style: Style) -> Line<'a> {
+    let query = query.chars().collect::<Vec<_>>();
+    let characters = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars())
+        .collect::<Vec<_>>();
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    let Some(start) = characters.windows(query.len().max(1)).position(|window| {
+        !query.is_empty() && window.iter().zip(&query).all(|(a, b)| same(*a, *b))
+    }) else {
+        return line;
+    };
+    let end = start + query.len();
+
+    let mut index = 0;
+    let mut spans = Vec::with_capacity(line.spans.len() + 2);
+    for span in line.spans {
+        let length = span.content.chars().count();
+        let span_start = index;
+        index += length;
+        if index <= start || span_start >= end {
+            spans.push(span);
+            continue;
+        }
+
+        let mut parts = [String::new(), String::new(), String::new()];
+        for (offset, character) in span.content.chars().enumerate() {
+            let position = span_start + offset;
+            let part = if position < start {
+                0
+            } else if position < end {
+                1
+            } else {
+                2
+            };
+            parts[part].push(character);
+        }
+        let [before, matched, after] = parts;
+        if !before.is_empty() {
+            spans.push(Span::styled(before, span.style));
+        }
+        spans.push(Span::styled(matched, span.style.patch(style)));
+        if !after.is_empty() {
+            spans.push(Span::styled(after, span.style));
+        }
+    }
+    Line { spans, ..line }
+}
+
+#[test]
+fn skip_columns_keeps_styles() {
+    use ratatui::style::{Color, Style};
+    let red = Style::new().fg(Color::Red);
+    let line = Line::from(vec![Span::raw("ab"), Span::styled("cde", red)]);
+    assert_eq!(skip_columns(line.clone(), 0), line);
+    assert_eq!(
+        skip_columns(line.clone(), 1),
+        Line::from(vec![Span::raw("b"), Span::styled("cde", red)])
+    );
+    assert_eq!(
+        skip_columns(line.clone(), 3),
+        Line::from(vec![Span::styled("de", red)])
+    );
+    assert_eq!(skip_columns(line, 10), Line::default());
+}
+
+#[test]
+fn skip_columns_replaces_cut_wide_characters() {
+    let line = Line::from("\u{4e2d}\u{6587}");
+    assert_eq!(skip_columns(line, 1), Line::from(" \u{6587}"));
+}
+
+#[test]
+fn wrap_keeps_styles() {
+    use ratatui::style::{Color, Style};
+    let red = Style::new().fg(Color::Red);
+    let line = Line::from(vec![Span::raw("ab"), Span::styled("cdefg", red)]);
+    assert_eq!(wrap(line.clone(), 10), std::slice::from_ref(&line));
+    assert_eq!(
+        wrap(line, 3),
+        [
+            Line::from(vec![Span::raw("ab"), Span::styled("c", red)]),
+            Line::from(vec![Span::styled("def", red)]),
+            Line::from(vec![Span::styled("g", red)]),
+        ]
+    );
+}
+
+#[test]
+fn highlight_first_splits_spans() {
+    use ratatui::style::Stylize;
+    let line = Line::from(vec![Span::raw("Foxt").red(), Span::raw("rot trot")]);
+    let style = Style::new().bold();
+    let highlighted = highlight_first(line, "TRO", style);
+    assert_eq!(
+        highlighted.spans,
+        [
+            Span::raw("Fox").red(),
+            Span::raw("t").red().bold(),
+            Span::raw("ro").bold(),
+            Span::raw("t trot"),
+        ]
+    );
+}
+
+#[test]
+fn highlight_first_without_match_is_unchanged() {
+    let line = Line::from("Foxtrot");
+    assert_eq!(highlight_first(line.clone(), "x!", Style::new()), line);
+    assert_eq!(highlight_first(line.clone(), "", Style::new()), line);
+    assert_eq!(
+        highlight_first(line.clone(), "Foxtrot and more", Style::new()),
+        line
+    );
+}
@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use unicode_width::UnicodeWidthStr;
+
+/// Draws the parts in front of the label of an item one after another.
+///
+/// Columns are counted from the start of the row which is the right edge when `mirrored`.
+/// Parts can also be drawn at the end of the row which then can not be used by the label.
+pub struct RowCursor {
+    area: Rect,
+    column: u16,
+    /// Columns used at the end of the row.
+    end: u16,
+    mirrored: bool,
+}
+
+impl RowCursor {
+    pub const fn new(area: Rect, mirrored: bool) -> Self {
+        Self {
+            area,
+            column: 0,
+            end: 0,
+            mirrored,
+        }
+    }
+
+    /// Columns from the start of the row which are already used.
+    pub const fn column(&self) -> u16 {
+        self.column
+    }
+
+    /// Columns which are not used yet.
+    pub const fn remaining(&self) -> u16 {
+        self.area
+            .width
+            .saturating_sub(self.column)
+            .saturating_sub(self.end)
+    }
+
+    /// Buffer x of something `width` wide starting at the `column` from the start of the row.
+    pub const fn x_of(&self, column: u16, width: u16) -> u16 {
+        if self.mirrored {
+            (self.area.x + self.area.width).saturating_sub(column + width)
+        } else {
+            self.area.x + column
+        }
+    }
+
+    /// Draw the `string` cut off at the remaining width onto the first line and move past it.
+    ///
+    /// Returns the columns of the buffer it was drawn to.
+    pub fn draw(&mut self, buf: &mut Buffer, string: &str, style: Style) -> Range<u16> {
+        let width = u16::try_from(string.width())
+            .unwrap_or(u16::MAX)
+            .min(self.remaining());
+        let x = self.x_of(self.column, width);
+        buf.set_stringn(x, self.area.y, string, width as usize, style);
+        self.column += width;
+        x..x + width
+    }
+
+    /// Draw the `string` cut off at the remaining width flush against the end of the row onto the first line.
+    ///
+    /// Returns the columns of the buffer it was drawn to.
+    pub fn draw_end(&mut self, buf: &mut Buffer, string: &str, style: Style) -> Range<u16> {
+        let width = u16::try_from(string.width())
+            .unwrap_or(u16::MAX)
+            .min(self.remaining());
+        let x = if self.mirrored {
+            self.area.x + self.end
+        } else {
+            (self.area.x + self.area.width).saturating_sub(self.end + width)
+        };
+        buf.set_stringn(x, self.area.y, string, width as usize, style);
+        self.end += width;
+        x..x + width
+    }
+
+    /// The area which is not used yet.
+    pub const fn rest(&self) -> Rect {
+        let width = self.remaining();
+        let x = if self.mirrored {
+            self.area.x + self.end
+        } else {
+            self.area.x + self.column
+        };
+        Rect {
+            x,
+            width,
+            ..self.area
+        }
+    }
+}
@@ -1,8 +1,13 @@
+use ratatui::style::Style;
 use ratatui::text::ToText;
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
+use crate::error::TreeError;
+use crate::iter::Iter;
+
 /// One item inside a [`Tree`](crate::Tree).
 ///
 /// Can have zero or more `children`.
@@ -32,7 +37,7 @@ use std::hash::{DefaultHasher, Hash, Hasher};
 /// # use managarr_tree_widget::TreeItem;
 /// let a = TreeItem::new_leaf("Leaf");
 /// let b = TreeItem::new("Root", vec![a])?;
-/// # Ok::<(), std::io::Error>(())
+/// # Ok::<(), managarr_tree_widget::TreeError>(())
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TreeItem<T>
@@ -41,7 +46,12 @@ where
 {
     pub(super) identifier: u64,
     pub(super) content: T,
+    /// Cached height of the `content` so it does not need to be converted on every render.
+    pub(super) height: usize,
     pub(super) children: Vec<Self>,
+    pub(super) style: Style,
+    pub(super) selectable: bool,
+    pub(super) icon: Option<&'static str>,
 }
 
 impl<T> TreeItem<T>
@@ -52,39 +62,58 @@ where
     ///
     /// # Errors
     ///
-    /// Errors when there are duplicate identifiers in the children.
-    pub fn new(content: T, children: Vec<Self>) -> std::io::Result<Self> {
-        let identifiers = children
-            .iter()
-            .map(|item| &item.identifier)
-            .collect::<HashSet<_>>();
-        if identifiers.len() != children.len() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::AlreadyExists,
-                "The children contain duplicate identifiers",
+    /// Errors with [`TreeError::DuplicateIdentifier`] when there are duplicate identifiers in the children.
+    /// Different children whose identifiers collide error with [`TreeError::IdentifierCollision`].
+    /// The path starts with the identifier of the item to be created.
+    pub fn new(content: T, children: Vec<Self>) -> Result<Self, TreeError> {
+        let identifier = content_identifier(&content);
+
+        if let Some((existing, duplicate)) = find_duplicate(&children) {
+            return Err(duplicate_error(
+                existing,
+                duplicate,
+                vec![identifier, duplicate.identifier],
+                vec![content.to_string(), duplicate.content.to_string()],
             ));
         }
 
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-
         Ok(Self {
-            identifier: hasher.finish(),
+            identifier,
+            height: content.to_text().height(),
             content,
             children,
+            style: Style::new(),
+            selectable: true,
+            icon: None,
         })
     }
 
+    /// Create a new `TreeItem` with children which might have failed to be created themselves.
+    ///
+    /// This allows to build a whole tree in one expression, see [`tree_item!`](crate::tree_item!).
+    ///
+    /// # Errors
+    ///
+    /// Errors with the first error of the `children` or the same way as [`TreeItem::new`].
+    pub fn from_nested(
+        content: T,
+        children: Vec<Result<Self, TreeError>>,
+    ) -> Result<Self, TreeError> {
+        let children = children.into_iter().collect::<Result<Vec<_>, _>>()?;
+        Self::new(content, children)
+    }
+
     /// Create a new `TreeItem` without children.
     #[must_use]
     pub fn new_leaf(content: T) -> Self {
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-
         Self {
-            identifier: hasher.finish(),
+            identifier: content_identifier(&content),
+            height: content.to_text().height(),
             content,
             children: Vec::new(),
+            style: Style::new(),
+            selectable: true,
+            icon: None,
         }
     }
 
@@ -105,6 +134,74 @@ where
         &self.children
     }
 
+    /// Whether this item has no children.
+    #[must_use]
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Whether this item has children and can therefore be opened.
+    #[must_use]
+    pub fn has_children(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// Iterate over this item and everything below it depth-first in pre-order.
+    ///
+    /// Each item comes with its identifier starting at this item.
+    /// See also [`iter_all`](crate::iter_all).
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(std::slice::from_ref(self))
+    }
+
+    /// Get the item of the given identifier by descending into the children.
+    ///
+    /// The identifier starts with the identifier of this item, just like the ones from [`iter`](Self::iter).
+    /// Returns `None` when any part of the identifier does not match.
+    #[must_use]
+    pub fn get(&self, identifier: &[u64]) -> Option<&Self> {
+        get_item(std::slice::from_ref(self), identifier)
+    }
+
+    /// Get a mutable reference to the item of the given identifier by descending into the children.
+    ///
+    /// See [`get`](Self::get).
+    /// When you choose to change the `identifier` the [`TreeState`](crate::TreeState) might not work as expected afterward.
+    #[must_use]
+    pub fn get_mut(&mut self, identifier: &[u64]) -> Option<&mut Self> {
+        let (first, rest) = identifier.split_first()?;
+        if *first != self.identifier {
+            return None;
+        }
+        rest.iter().try_fold(self, |item, identifier| {
+            item.children
+                .iter_mut()
+                .find(|child| child.identifier == *identifier)
+        })
+    }
+
+    /// Replace the content while keeping the identifier.
+    ///
+    /// The identifier is not derived from the new content, so the [`TreeState`](crate::TreeState) stays valid.
+    /// Afterwards the identifier is no longer the hash of the content:
+    /// A sibling added later with the old content has the same identifier and errors with [`TreeError::DuplicateIdentifier`].
+    pub fn set_content(&mut self, content: T) {
+        self.height = content.to_text().height();
+        self.content = content;
+    }
+
+    /// Replace the content of the item of the given identifier while keeping its identifier.
+    ///
+    /// See [`get`](Self::get) and [`set_content`](Self::set_content).
+    /// Returns `false` when there is no item with the given identifier.
+    pub fn update_content_at(&mut self, identifier: &[u64], content: T) -> bool {
+        let Some(item) = self.get_mut(identifier) else {
+            return false;
+        };
+        item.set_content(content);
+        true
+    }
+
     /// Get a reference to a child by index.
     #[must_use]
     pub fn child(&self, index: usize) -> Option<&Self> {
@@ -119,32 +216,208 @@ where
         self.children.get_mut(index)
     }
 
+    /// Set the style of this item.
+    ///
+    /// It is applied to the whole row of the item below the style of the content.
+    #[must_use]
+    pub const fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set whether this item can be selected by navigating with the keyboard. Defaults to `true`.
+    ///
+    /// Items which are not selectable are still rendered and can still be opened and closed.
+    #[must_use]
+    pub const fn selectable(mut self, selectable: bool) -> Self {
+        self.selectable = selectable;
+        self
+    }
+
+    /// Set an icon like a file type symbol which is drawn between the node symbol and the label.
+    ///
+    /// Items without an icon take up no space for it.
     #[must_use]
-    pub fn height(&self) -> usize {
-        self.content.clone().to_text().height()
+    pub const fn icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Whether this item can be selected by navigating with the keyboard, see [`selectable`](Self::selectable).
+    #[must_use]
+    pub const fn is_selectable(&self) -> bool {
+        self.selectable
+    }
+
+    /// Height of the content in lines.
+    ///
+    /// This is computed once when the item is created.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The content as plain text. Styling is dropped and lines are joined with a newline.
+    pub(crate) fn plain_text(&self) -> String {
+        self.content
+            .to_text()
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Amount of all nodes below this item, recursively.
+    #[must_use]
+    pub fn descendant_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| 1 + child.descendant_count())
+            .sum()
+    }
+
+    /// Amount of nodes without children in this item, including itself when it has no children.
+    #[must_use]
+    pub fn leaf_count(&self) -> usize {
+        if self.is_leaf() {
+            1
+        } else {
+            self.children.iter().map(Self::leaf_count).sum()
+        }
     }
 
     /// Add a child to the `TreeItem`.
     ///
     /// # Errors
     ///
-    /// Errors when the `identifier` of the `child` already exists in the children.
-    pub fn add_child(&mut self, child: Self) -> std::io::Result<()> {
-        let existing = self
+    /// Errors with [`TreeError::DuplicateIdentifier`] when the `identifier` of the `child` already exists in the children.
+    pub fn add_child(&mut self, child: Self) -> Result<(), TreeError> {
+        self.insert_child(self.children.len(), child)
+    }
+
+    /// Add a child to the `TreeItem` and return it for chaining.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::TreeItem;
+    /// let root = TreeItem::new_leaf("Root")
+    ///     .with_child(TreeItem::new_leaf("a"))?
+    ///     .with_child(TreeItem::new_leaf("b"))?;
+    /// assert_eq!(root.children().len(), 2);
+    /// # Ok::<(), managarr_tree_widget::TreeError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors the same way as [`add_child`](Self::add_child).
+    pub fn with_child(mut self, child: Self) -> Result<Self, TreeError> {
+        self.add_child(child)?;
+        Ok(self)
+    }
+
+    /// Insert a child into the `TreeItem` at the given `index`.
+    ///
+    /// The `index` is clamped to the amount of children, so anything past the end appends the child.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`TreeError::DuplicateIdentifier`] when the `identifier` of the `child` already exists in the children.
+    pub fn insert_child(&mut self, index: usize, child: Self) -> Result<(), TreeError> {
+        if let Some(existing) = self
             .children
             .iter()
-            .map(|item| &item.identifier)
-            .collect::<HashSet<_>>();
-        if existing.contains(&child.identifier) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::AlreadyExists,
-                "identifier already exists in the children",
+            .find(|item| item.identifier == child.identifier)
+        {
+            return Err(duplicate_error(
+                existing,
+                &child,
+                vec![self.identifier, child.identifier],
+                vec![self.content.to_string(), child.content.to_string()],
             ));
         }
 
-        self.children.push(child);
+        let index = index.min(self.children.len());
+        self.children.insert(index, child);
         Ok(())
     }
+
+    /// Stable sort the direct children with the given comparison function.
+    ///
+    /// The identifiers are not changed so the [`TreeState`](crate::TreeState) stays valid.
+    pub fn sort_children_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&Self, &Self) -> Ordering,
+    {
+        self.children.sort_by(compare);
+    }
+
+    /// Stable sort the children on every level with the given comparison function.
+    ///
+    /// See [`sort_children_by`](Self::sort_children_by).
+    pub fn sort_children_by_recursive<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Self, &Self) -> Ordering,
+    {
+        self.sort_recursive(&mut compare);
+    }
+
+    fn sort_recursive<F>(&mut self, compare: &mut F)
+    where
+        F: FnMut(&Self, &Self) -> Ordering,
+    {
+        self.children.sort_by(&mut *compare);
+        for child in &mut self.children {
+            child.sort_recursive(compare);
+        }
+    }
+}
+
+/// Create a [`TreeItem`] with nested children in one expression.
+///
+/// A child is either a leaf or a parenthesized content with its children.
+/// Leaves consisting of more than a single token need to be wrapped in parentheses.
+/// The result is a [`Result`] as duplicate identifiers are rejected with a [`TreeError`] the same way as [`TreeItem::new`].
+///
+/// ```
+/// # use managarr_tree_widget::tree_item;
+/// let bravo = tree_item!("Bravo", ["Charlie", ("Delta", ["Echo", "Foxtrot"]), "Golf"])?;
+/// assert_eq!(bravo.children().len(), 3);
+/// assert_eq!(bravo.children()[1].children().len(), 2);
+///
+/// assert!(tree_item!("Bravo", ["Charlie", "Charlie"]).is_err());
+/// # Ok::<(), managarr_tree_widget::TreeError>(())
+/// ```
+#[macro_export]
+macro_rules! tree_item {
+    ($content:expr, [$($child:tt),* $(,)?]) => {
+        $crate::TreeItem::from_nested($content, vec![$($crate::tree_item!($child)),*])
+    };
+    (($content:expr, [$($child:tt),* $(,)?])) => {
+        $crate::tree_item!($content, [$($child),*])
+    };
+    ($content:expr) => {
+        ::std::result::Result::<_, $crate::TreeError>::Ok($crate::TreeItem::new_leaf($content))
+    };
+}
+
+/// Get the item of the given identifier by descending into the `items`.
+#[must_use]
+pub fn get_item<'a, T>(items: &'a [TreeItem<T>], identifier: &[u64]) -> Option<&'a TreeItem<T>>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    let (first, rest) = identifier.split_first()?;
+    let item = items.iter().find(|item| item.identifier == *first)?;
+    rest.iter().try_fold(item, |item, identifier| {
+        item.children
+            .iter()
+            .find(|child| child.identifier == *identifier)
+    })
 }
 
 impl TreeItem<&'static str> {
@@ -171,8 +444,71 @@ impl TreeItem<&'static str> {
     }
 }
 
+/// Find the first two `items` with the same identifier.
+pub fn find_duplicate<T>(items: &[TreeItem<T>]) -> Option<(&TreeItem<T>, &TreeItem<T>)>
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    let mut seen = HashMap::with_capacity(items.len());
+    items.iter().find_map(|item| {
+        seen.insert(item.identifier, item)
+            .map(|existing| (existing, item))
+    })
+}
+
+/// The identifier of an item created with the given `content`.
+fn content_identifier<T>(content: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Error for two items with the same identifier at the given `path` with the content of its items as `labels`.
+///
+/// It is only a hash collision when both identifiers are still the hashes of their different contents.
+/// Otherwise the content of one of them was replaced with [`TreeItem::set_content`] and the identifiers are duplicates.
+pub fn duplicate_error<T>(
+    existing: &TreeItem<T>,
+    other: &TreeItem<T>,
+    path: Vec<u64>,
+    labels: Vec<String>,
+) -> TreeError
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    let is_collision = existing.content != other.content
+        && existing.identifier == content_identifier(&existing.content)
+        && other.identifier == content_identifier(&other.content);
+    if is_collision {
+        TreeError::IdentifierCollision { path, labels }
+    } else {
+        TreeError::DuplicateIdentifier { path, labels }
+    }
+}
+
+/// Amount of all nodes in the `items` including all their descendants.
+#[must_use]
+pub fn item_count<T>(items: &[TreeItem<T>]) -> usize
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    items.iter().map(|item| 1 + item.descendant_count()).sum()
+}
+
+/// Amount of nodes without children in the `items`.
+#[must_use]
+pub fn leaf_count<T>(items: &[TreeItem<T>]) -> usize
+where
+    T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+{
+    items.iter().map(TreeItem::leaf_count).sum()
+}
+
 #[test]
-#[should_panic = "duplicate identifiers"]
+#[should_panic = "DuplicateIdentifier"]
 fn tree_item_new_errors_with_duplicate_identifiers() {
     let item = TreeItem::new_leaf("text");
     let another = item.clone();
@@ -180,10 +516,236 @@ fn tree_item_new_errors_with_duplicate_identifiers() {
 }
 
 #[test]
-#[should_panic = "identifier already exists"]
+#[should_panic = "DuplicateIdentifier"]
 fn tree_item_add_child_errors_with_duplicate_identifiers() {
     let item = TreeItem::new_leaf("text");
     let another = item.clone();
     let mut root = TreeItem::new("Root", vec![item]).unwrap();
     root.add_child(another).unwrap();
 }
+
+#[test]
+#[should_panic = "DuplicateIdentifier"]
+fn tree_item_with_child_errors_with_duplicate_identifiers() {
+    let _ = TreeItem::new_leaf("Root")
+        .with_child(TreeItem::new_leaf("text"))
+        .unwrap()
+        .with_child(TreeItem::new_leaf("text"))
+        .unwrap();
+}
+
+#[test]
+fn tree_item_from_nested() {
+    let items = [
+        tree_item!("Alfa").unwrap(),
+        tree_item!("Bravo", ["Charlie", ("Delta", ["Echo", "Foxtrot"]), "Golf"]).unwrap(),
+        tree_item!("Hotel", []).unwrap(),
+    ];
+    assert_eq!(items.as_slice(), TreeItem::example());
+
+    let error = TreeItem::from_nested(
+        "Root",
+        vec![tree_item!("a", ["b", "b"]), Ok(TreeItem::new_leaf("c"))],
+    )
+    .unwrap_err();
+    let path = vec![
+        TreeItem::new_leaf("a").identifier(),
+        TreeItem::new_leaf("b").identifier(),
+    ];
+    let labels = vec!["a".to_owned(), "b".to_owned()];
+    assert_eq!(error, TreeError::DuplicateIdentifier { path, labels });
+}
+
+#[test]
+fn tree_item_is_leaf_or_has_children() {
+    let items = TreeItem::example();
+    assert!(items[0].is_leaf());
+    assert!(!items[0].has_children());
+    assert!(!items[1].is_leaf());
+    assert!(items[1].has_children());
+}
+
+#[test]
+fn tree_item_height() {
+    assert_eq!(TreeItem::new_leaf("one").height(), 1);
+    assert_eq!(TreeItem::new_leaf("one\ntwo").height(), 2);
+    let root = TreeItem::new("one\ntwo\nthree", vec![]).unwrap();
+    assert_eq!(root.height(), 3);
+}
+
+#[test]
+fn tree_item_insert_child() {
+    let mut root = TreeItem::new("Root", vec![TreeItem::new_leaf("b")]).unwrap();
+    root.insert_child(0, TreeItem::new_leaf("a")).unwrap();
+    root.insert_child(2, TreeItem::new_leaf("c")).unwrap();
+    root.insert_child(42, TreeItem::new_leaf("d")).unwrap();
+    let contents = root
+        .children()
+        .iter()
+        .map(TreeItem::content)
+        .collect::<Vec<_>>();
+    assert_eq!(contents, [&"a", &"b", &"c", &"d"]);
+}
+
+#[test]
+#[should_panic = "DuplicateIdentifier"]
+fn tree_item_insert_child_errors_with_duplicate_identifiers() {
+    let mut root = TreeItem::new("Root", vec![TreeItem::new_leaf("text")]).unwrap();
+    root.insert_child(0, TreeItem::new_leaf("text")).unwrap();
+}
+
+#[test]
+fn tree_item_sort_children_by() {
+    let mut root = TreeItem::new(
+        "Root",
+        vec![
+            TreeItem::new_leaf("b.rs"),
+            TreeItem::new(
+                "src",
+                vec![TreeItem::new_leaf("z"), TreeItem::new_leaf("y")],
+            )
+            .unwrap(),
+            TreeItem::new_leaf("a.rs"),
+            TreeItem::new("docs", vec![TreeItem::new_leaf("README.md")]).unwrap(),
+        ],
+    )
+    .unwrap();
+    let identifiers = root
+        .children()
+        .iter()
+        .map(TreeItem::identifier)
+        .collect::<std::collections::HashSet<_>>();
+    let directories_first = |a: &TreeItem<&str>, b: &TreeItem<&str>| {
+        a.is_leaf()
+            .cmp(&b.is_leaf())
+            .then_with(|| a.content().cmp(b.content()))
+    };
+
+    root.sort_children_by(directories_first);
+    let contents = root
+        .children()
+        .iter()
+        .map(TreeItem::content)
+        .collect::<Vec<_>>();
+    assert_eq!(contents, [&"docs", &"src", &"a.rs", &"b.rs"]);
+    assert_eq!(root.children()[1].children()[0].content(), &"z");
+    assert_eq!(
+        root.children()
+            .iter()
+            .map(TreeItem::identifier)
+            .collect::<std::collections::HashSet<_>>(),
+        identifiers
+    );
+
+    root.sort_children_by_recursive(directories_first);
+    assert_eq!(root.children()[1].children()[0].content(), &"y");
+    assert!(root.add_child(TreeItem::new_leaf("a.rs")).is_err());
+}
+
+#[test]
+fn tree_item_get_by_identifier() {
+    let mut items = TreeItem::example();
+    let bravo = &mut items[1];
+    let delta = bravo.children()[1].identifier();
+    let echo = bravo.children()[1].children()[0].identifier();
+    let identifier = [bravo.identifier(), delta, echo];
+
+    assert_eq!(bravo.get(&identifier).unwrap().content(), &"Echo");
+    assert_eq!(bravo.get(&identifier[..1]).unwrap().content(), &"Bravo");
+    assert!(bravo.get(&identifier[1..]).is_none());
+    assert!(bravo.get(&[identifier[0], echo]).is_none());
+    assert!(bravo.get(&[]).is_none());
+
+    bravo.get_mut(&identifier).unwrap().content = "Changed";
+    assert_eq!(bravo.get(&identifier).unwrap().content(), &"Changed");
+}
+
+#[test]
+fn tree_item_counts() {
+    let items = TreeItem::example();
+    let bravo = &items[1];
+    assert_eq!(bravo.descendant_count(), 5);
+    assert_eq!(bravo.leaf_count(), 4);
+    assert_eq!(items[0].descendant_count(), 0);
+    assert_eq!(items[0].leaf_count(), 1);
+    assert_eq!(item_count(&items), 8);
+    assert_eq!(leaf_count(&items), 6);
+    assert_eq!(item_count::<&str>(&[]), 0);
+}
+
+/// Content which always hashes the same to simulate identifier collisions.
+#[cfg(test)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Colliding(&'static str);
+
+#[cfg(test)]
+impl Hash for Colliding {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u8(0);
+    }
+}
+
+#[cfg(test)]
+impl Display for Colliding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[test]
+fn tree_item_reports_identifier_collisions() {
+    let a = TreeItem::new_leaf(Colliding("a"));
+    let b = TreeItem::new_leaf(Colliding("b"));
+    assert_eq!(a.identifier(), b.identifier());
+
+    let root = TreeItem::new_leaf(Colliding("root"));
+    let error = TreeItem::new(Colliding("root"), vec![a.clone(), b.clone()]).unwrap_err();
+    let path = vec![root.identifier(), b.identifier()];
+    let labels = vec!["root".to_owned(), "b".to_owned()];
+    assert_eq!(error, TreeError::IdentifierCollision { path, labels });
+    assert_eq!(
+        error.to_string(),
+        r#"identifiers of different items collide at ["root", "b"]"#
+    );
+
+    let mut root = TreeItem::new(Colliding("root"), vec![a.clone()]).unwrap();
+    let path = vec![root.identifier(), b.identifier()];
+    let error = root.add_child(b).unwrap_err();
+    let labels = vec!["root".to_owned(), "b".to_owned()];
+    assert_eq!(error, TreeError::IdentifierCollision { path, labels });
+
+    let path = vec![root.identifier(), a.identifier()];
+    let error = root.add_child(a).unwrap_err();
+    let labels = vec!["root".to_owned(), "a".to_owned()];
+    assert_eq!(error, TreeError::DuplicateIdentifier { path, labels });
+}
+
+#[test]
+fn tree_item_with_replaced_content_reports_duplicates() {
+    let mut root = TreeItem::new("root", vec![TreeItem::new_leaf("a")]).unwrap();
+    let a = root.children()[0].identifier();
+    root.child_mut(0).unwrap().set_content("renamed");
+    assert_eq!(root.children()[0].identifier(), a);
+
+    let error = root.add_child(TreeItem::new_leaf("a")).unwrap_err();
+    let path = vec![root.identifier(), a];
+    let labels = vec!["root".to_owned(), "a".to_owned()];
+    assert_eq!(error, TreeError::DuplicateIdentifier { path, labels });
+}
+
+#[test]
+fn tree_item_update_content_at() {
+    let mut items = TreeItem::example();
+    let bravo = &mut items[1];
+    let delta = bravo.children()[1].identifier();
+    let echo = bravo.children()[1].children()[0].identifier();
+    let identifier = [bravo.identifier(), delta, echo];
+
+    assert!(bravo.update_content_at(&identifier, "Echo\nRenamed"));
+    let echo_item = bravo.get(&identifier).unwrap();
+    assert_eq!(echo_item.content(), &"Echo\nRenamed");
+    assert_eq!(echo_item.identifier(), echo);
+    assert_eq!(echo_item.height(), 2);
+
+    assert!(!bravo.update_content_at(&[bravo.identifier(), echo], "Missing"));
+}
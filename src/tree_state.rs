@@ -1,11 +1,14 @@
 use ratatui::layout::{Position, Rect};
 use ratatui::text::ToText;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Display;
 use std::hash::Hash;
+use std::ops::Range;
 
-use crate::flatten::{flatten, Flattened};
-use crate::tree_item::TreeItem;
+use crate::flatten::{flatten, flatten_iter, Flattened};
+use crate::iter::iter_all;
+use crate::tree_item::{get_item, TreeItem};
+use crate::Tree;
 
 /// Keeps the state of what is currently selected and what was opened in a [`Tree`](crate::Tree).
 ///
@@ -20,41 +23,421 @@ use crate::tree_item::TreeItem;
 #[derive(Debug, Default)]
 pub struct TreeState {
     pub(super) offset: usize,
+    pub(super) horizontal_scroll: usize,
     pub(super) opened: HashSet<Vec<u64>>,
+    /// Maximum amount of open nodes, see [`TreeState::set_open_limit`]
+    pub(super) open_limit: Option<usize>,
+    /// Open nodes from the least to the most recently opened while limited
+    pub(super) open_order: VecDeque<Vec<u64>>,
     pub(super) selected: Vec<u64>,
+    pub(super) multi_selected: HashSet<Vec<u64>>,
+    pub(super) checked: HashSet<Vec<u64>>,
     pub(super) ensure_selected_in_view_on_next_render: bool,
+    /// Most recently opened node without children, see [`TreeState::take_pending_open`]
+    pub(super) pending_open: Option<Vec<u64>>,
+    /// Recently opened nodes with the amount of their descendants shown so far, see [`Tree::reveal_step`](crate::Tree::reveal_step)
+    pub(super) revealing: HashMap<Vec<u64>, usize>,
+    /// Identifier to reveal on next render, see [`TreeState::scroll_to`]
+    pub(super) scroll_to_on_next_render: Option<Vec<u64>>,
 
     pub(super) last_area: Rect,
     pub(super) last_biggest_index: usize,
+    pub(super) last_max_horizontal_scroll: usize,
     /// All identifiers open on last render
     pub(super) last_identifiers: Vec<Vec<u64>>,
-    /// Identifier rendered at `y` on last render
-    pub(super) last_rendered_identifiers: Vec<(u16, Vec<u64>)>,
+    /// Height of each of the `last_identifiers` on last render
+    pub(super) last_heights: Vec<usize>,
+    /// Whether each of the `last_identifiers` is selectable on last render
+    pub(super) last_selectable: Vec<bool>,
+    /// Whether each of the `last_identifiers` has children on last render
+    pub(super) last_has_children: Vec<bool>,
+    /// Index of each of the `last_identifiers`
+    pub(super) last_indices: HashMap<Vec<u64>, usize>,
+    /// Whether [`key_up`](Self::key_up) and [`key_down`](Self::key_down) wrap around, taken from the [`Tree`](crate::Tree) on last render
+    pub(super) last_wrap_navigation: bool,
+    /// Whether selecting a node opens it, taken from the [`Tree`](crate::Tree) on last render
+    pub(super) last_open_on_select: bool,
+    /// Items drawn on last render
+    pub(super) last_rendered: Vec<RenderedItem>,
+}
+
+/// Only the selection, open nodes and offset are persisted.
+/// Everything else is only relevant for the next render and starts out empty.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TreeState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(serde::Serialize)]
+        struct Persisted<'a> {
+            offset: usize,
+            opened: &'a HashSet<Vec<u64>>,
+            selected: &'a [u64],
+        }
+
+        Persisted {
+            offset: self.offset,
+            opened: &self.opened,
+            selected: &self.selected,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TreeState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct Persisted {
+            offset: usize,
+            opened: HashSet<Vec<u64>>,
+            selected: Vec<u64>,
+        }
+
+        let Persisted {
+            offset,
+            opened,
+            selected,
+        } = Persisted::deserialize(deserializer)?;
+        Ok(Self {
+            offset,
+            opened,
+            selected,
+            ..Self::default()
+        })
+    }
+}
+
+/// An item drawn on last render.
+#[derive(Debug)]
+pub struct RenderedItem {
+    pub(super) identifier: Vec<u64>,
+    pub(super) area: Rect,
+    /// Columns of the open / close symbol. `None` for items without children.
+    pub(super) symbol: Option<Range<u16>>,
+}
+
+/// The part of an item hit by a position, see [`TreeState::toggle_target_at`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleHit {
+    /// The open / close symbol of an item with children.
+    Symbol,
+    /// Anywhere else on the item.
+    Label,
+}
+
+/// What [`TreeState::toggle_at`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleAction {
+    /// An item with children was opened or closed.
+    Toggled,
+    /// An item without children was selected and opened, see [`TreeState::take_pending_open`].
+    Selected,
+    /// Nothing was rendered at the position.
+    Nothing,
 }
 
 impl TreeState {
     #[must_use]
+    #[deprecated = "Use self.offset()"]
     pub const fn get_offset(&self) -> usize {
         self.offset
     }
 
+    /// Index of the first visible item, see [`flatten`](Self::flatten).
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// How far the items are scrolled down from `0.0` at the top to `1.0` at the bottom.
+    ///
+    /// The items and viewport height of the last render are used, so this is `0.0` when everything fits.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn scroll_progress(&self) -> f32 {
+        let max_offset = max_offset(&self.last_heights, self.last_area.height as usize);
+        if max_offset == 0 {
+            return 0.0;
+        }
+        self.offset.min(max_offset) as f32 / max_offset as f32
+    }
+
+    /// Scroll so the item with the given index is the first visible one.
+    ///
+    /// The selection is not moved. The offset is clamped on next render so the last page stays filled.
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
     #[must_use]
     #[deprecated = "Use self.opened()"]
     pub fn get_all_opened(&self) -> Vec<Vec<u64>> {
         self.opened.iter().cloned().collect()
     }
 
+    /// Get the identifiers of all open nodes.
+    ///
+    /// Together with [`set_opened`](Self::set_opened) this allows to persist which nodes are open.
     #[must_use]
     pub const fn opened(&self) -> &HashSet<Vec<u64>> {
         &self.opened
     }
 
+    /// Whether the node of the given identifier is open.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::TreeState;
+    /// let mut state = TreeState::default();
+    /// assert!(!state.is_open(&[2]));
+    /// state.open(vec![2]);
+    /// assert!(state.is_open(&[2]));
+    /// assert!(!state.is_open(&[2, 4]));
+    /// ```
+    #[must_use]
+    pub fn is_open(&self, identifier: &[u64]) -> bool {
+        self.opened.contains(identifier)
+    }
+
+    /// Replace all open nodes, for example with ones persisted from [`opened`](Self::opened).
+    ///
+    /// Identifiers which do not exist (anymore) in the items are harmless as they are ignored when flattening.
+    pub fn set_opened(&mut self, opened: HashSet<Vec<u64>>) {
+        self.opened = opened;
+        self.forget_closed();
+        self.track_opened();
+    }
+
+    /// Get the identifier of the current selection. Empty when nothing is selected.
+    ///
+    /// Together with [`set_selected`](Self::set_selected) this allows to persist the selection.
     #[must_use]
     pub fn selected(&self) -> &[u64] {
         &self.selected
     }
 
+    /// Whether the given identifier is the current selection.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::TreeState;
+    /// let mut state = TreeState::default();
+    /// state.select(vec![2, 4]);
+    /// assert!(state.is_selected(&[2, 4]));
+    /// assert!(!state.is_selected(&[2]));
+    /// ```
+    #[must_use]
+    pub fn is_selected(&self, identifier: &[u64]) -> bool {
+        self.selected == identifier
+    }
+
+    /// Get the currently selected [`TreeItem`] from the `items`.
+    ///
+    /// Returns `None` when nothing is selected or the selection does not exist in the `items` (anymore).
+    #[must_use]
+    pub fn selected_item<'a, T>(&self, items: &'a [TreeItem<T>]) -> Option<&'a TreeItem<T>>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        get_item(items, &self.selected)
+    }
+
+    /// Height of the area the items were rendered into on last render.
+    ///
+    /// Useful as the amount of lines for [`select_page_up`](Self::select_page_up) and [`select_page_down`](Self::select_page_down).
+    #[must_use]
+    pub const fn viewport_height(&self) -> u16 {
+        self.last_area.height
+    }
+
+    /// Columns needed to show the widest visible item of the `tree` without cutting it off.
+    ///
+    /// This includes the highlight symbol, indentation, symbols and decorations in front of the label but neither the block nor the scrollbar.
+    /// All visible items count, not only the ones scrolled into view, so the width stays the same while scrolling.
+    /// Useful to size the area of the tree to its content before rendering it.
+    #[must_use]
+    pub fn max_visible_width<T>(&self, tree: &Tree<'_, T>) -> u16
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        u16::try_from(tree.max_visible_width(self)).unwrap_or(u16::MAX)
+    }
+
+    /// Get the `(content_length, position)` for an external scrollbar.
+    ///
+    /// The content length is the amount of visible items on last render (including by scrolling) and the position is the current offset.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::TreeState;
+    /// # use ratatui::widgets::ScrollbarState;
+    /// # let state = TreeState::default();
+    /// let (content_length, position) = state.scrollbar_state();
+    /// let scrollbar_state = ScrollbarState::new(content_length).position(position);
+    /// ```
+    #[must_use]
+    pub fn scrollbar_state(&self) -> (usize, usize) {
+        (self.last_identifiers.len(), self.offset)
+    }
+
+    /// All identifiers which are part of the multi-selection.
+    ///
+    /// See [`toggle_selected_multi`](Self::toggle_selected_multi).
+    #[must_use]
+    pub const fn selected_multi(&self) -> &HashSet<Vec<u64>> {
+        &self.multi_selected
+    }
+
+    /// Get the identifiers of all checked nodes, see [`set_checked`](Self::set_checked).
+    #[must_use]
+    pub const fn checked_paths(&self) -> &HashSet<Vec<u64>> {
+        &self.checked
+    }
+
+    /// Check or uncheck the node of the given identifier together with all of its descendants.
+    ///
+    /// Its ancestors are checked when all of their children are checked and unchecked otherwise.
+    /// Nodes with only some checked descendants are shown as partially checked by [`Tree::checkbox_symbols`](crate::Tree::checkbox_symbols).
+    ///
+    /// Returns `true` when any node changed.
+    /// Returns `false` when nothing changed or the identifier does not exist in the `items`.
+    pub fn set_checked<T>(
+        &mut self,
+        items: &[TreeItem<T>],
+        identifier: &[u64],
+        checked: bool,
+    ) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let Some(((_, parent), item)) = identifier.split_last().zip(get_item(items, identifier))
+        else {
+            return false;
+        };
+
+        let mut changed = false;
+        for (below, _) in item {
+            let mut path = parent.to_vec();
+            path.extend(below);
+            changed |= if checked {
+                self.checked.insert(path)
+            } else {
+                self.checked.remove(&path)
+            };
+        }
+
+        for end in (1..identifier.len()).rev() {
+            let ancestor = &identifier[..end];
+            let Some(ancestor_item) = get_item(items, ancestor) else {
+                break;
+            };
+            let all_checked = ancestor_item.children.iter().all(|child| {
+                let mut path = ancestor.to_vec();
+                path.push(child.identifier);
+                self.checked.contains(&path)
+            });
+            changed |= if all_checked {
+                self.checked.insert(ancestor.to_vec())
+            } else {
+                self.checked.remove(ancestor)
+            };
+        }
+        changed
+    }
+
+    /// Identifiers of the nodes which are not checked themselves but have checked descendants within the `items`.
+    ///
+    /// Computed bottom-up in a single pass over the `items` instead of searching below every node.
+    pub(crate) fn partially_checked<T>(&self, items: &[TreeItem<T>]) -> HashSet<Vec<u64>>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let mut partial = HashSet::new();
+        if self.checked.is_empty() {
+            return partial;
+        }
+
+        let mut identifier = Vec::new();
+        // Children still to be visited together with whether any node below their parent is checked
+        let mut stack = vec![(items.iter(), false)];
+        while let Some((children, _)) = stack.last_mut() {
+            if let Some(item) = children.next() {
+                identifier.push(item.identifier);
+                stack.push((item.children.iter(), false));
+                continue;
+            }
+
+            let Some((_, any_below)) = stack.pop() else {
+                break;
+            };
+            let Some((_, parent_any_below)) = stack.last_mut() else {
+                // The top level is done
+                break;
+            };
+            let is_checked = self.checked.contains(&identifier);
+            if any_below && !is_checked {
+                partial.insert(identifier.clone());
+            }
+            *parent_any_below |= any_below || is_checked;
+            identifier.pop();
+        }
+        partial
+    }
+
+    /// Clear the selection, open nodes, scroll position, settings and everything remembered from the last render.
+    ///
+    /// Afterwards the state is equivalent to [`TreeState::default`] while keeping its allocations.
+    /// This includes removing the [open limit](Self::set_open_limit).
+    pub fn reset(&mut self) {
+        self.offset = 0;
+        self.horizontal_scroll = 0;
+        self.opened.clear();
+        self.open_limit = None;
+        self.open_order.clear();
+        self.selected.clear();
+        self.multi_selected.clear();
+        self.checked.clear();
+        self.ensure_selected_in_view_on_next_render = false;
+        self.pending_open = None;
+        self.revealing.clear();
+        self.scroll_to_on_next_render = None;
+
+        self.last_area = Rect::default();
+        self.last_biggest_index = 0;
+        self.last_max_horizontal_scroll = 0;
+        self.last_identifiers.clear();
+        self.last_heights.clear();
+        self.last_selectable.clear();
+        self.last_has_children.clear();
+        self.last_indices.clear();
+        self.last_wrap_navigation = false;
+        self.last_open_on_select = false;
+        self.last_rendered.clear();
+    }
+
     /// Get a flat list of all currently viewable (including by scrolling) [`TreeItem`]s with this `TreeState`.
+    ///
+    /// Only the children of [`opened`](Self::opened) nodes are included, in the same order as they are rendered.
+    /// This is useful to build a custom scrollbar or to count the visible rows.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::{TreeItem, TreeState};
+    /// let items = vec![
+    ///     TreeItem::new("Bravo", vec![TreeItem::new_leaf("Charlie")])?,
+    ///     TreeItem::new_leaf("Hotel"),
+    /// ];
+    /// let mut state = TreeState::default();
+    /// assert_eq!(state.flatten(&items).len(), 2);
+    ///
+    /// state.open(vec![items[0].identifier()]);
+    /// let visible = state.flatten(&items);
+    /// assert_eq!(visible.len(), 3);
+    /// assert_eq!(visible[1].item.content(), &"Charlie");
+    /// assert_eq!(visible[1].depth(), 1);
+    /// # Ok::<(), managarr_tree_widget::TreeError>(())
+    /// ```
     #[must_use]
     pub fn flatten<'a, T>(&self, items: &'a [TreeItem<T>]) -> Vec<Flattened<'a, T>>
     where
@@ -63,8 +446,109 @@ impl TreeState {
         flatten(&self.opened, items, &[])
     }
 
+    /// Lazily iterate over the same items as [`flatten`](Self::flatten) without collecting them.
+    ///
+    /// Useful to only look at a window of a huge tree with `skip` and `take`.
+    pub fn flatten_iter<'a, T>(
+        &'a self,
+        items: &'a [TreeItem<T>],
+    ) -> impl Iterator<Item = Flattened<'a, T>> + 'a
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        flatten_iter(&self.opened, items, &[])
+    }
+
+    /// Amount of currently viewable [`TreeItem`]s, see [`flatten`](Self::flatten).
+    #[must_use]
+    pub fn visible_count<T>(&self, items: &[TreeItem<T>]) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.flatten_iter(items).count()
+    }
+
+    /// Identifiers of the currently viewable [`TreeItem`]s, see [`flatten`](Self::flatten).
+    #[must_use]
+    pub fn visible_identifiers<T>(&self, items: &[TreeItem<T>]) -> Vec<Vec<u64>>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.flatten_iter(items)
+            .map(|flattened| flattened.identifier)
+            .collect()
+    }
+
+    /// Hide the descendants of recently opened nodes which are not revealed yet and reveal `step` more of them.
+    ///
+    /// Nodes are no longer tracked once all of their descendants are shown.
+    /// With a `step` of `0` everything is shown at once.
+    pub(crate) fn advance_reveal<'a, T>(
+        &mut self,
+        visible: Vec<Flattened<'a, T>>,
+        step: usize,
+    ) -> Vec<Flattened<'a, T>>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        if step == 0 || self.revealing.is_empty() {
+            self.revealing.clear();
+            return visible;
+        }
+
+        // Revealing nodes the current item is within: (identifier, remaining, whether anything was hidden)
+        let mut within: Vec<(Vec<u64>, usize, bool)> = Vec::new();
+        let mut still_revealing = HashMap::new();
+        let mut shown = Vec::with_capacity(visible.len());
+        for flattened in visible {
+            while within
+                .last()
+                .is_some_and(|(identifier, _, _)| !flattened.identifier.starts_with(identifier))
+            {
+                let (identifier, _, hid) = within.pop().expect("checked to exist");
+                if hid {
+                    let revealed = self.revealing[&identifier];
+                    still_revealing.insert(identifier, revealed);
+                }
+            }
+
+            if within.iter().any(|&(_, remaining, _)| remaining == 0) {
+                for (_, remaining, hid) in &mut within {
+                    *hid |= *remaining == 0;
+                }
+                continue;
+            }
+            for (_, remaining, _) in &mut within {
+                *remaining -= 1;
+            }
+            if let Some(revealed) = self.revealing.get_mut(&flattened.identifier) {
+                *revealed = revealed.saturating_add(step);
+                within.push((flattened.identifier.clone(), *revealed, false));
+            }
+            shown.push(flattened);
+        }
+        for (identifier, _, hid) in within {
+            if hid {
+                let revealed = self.revealing[&identifier];
+                still_revealing.insert(identifier, revealed);
+            }
+        }
+        self.revealing = still_revealing;
+        shown
+    }
+
+    /// Whether recently opened nodes are still being revealed, see [`Tree::reveal_step`](crate::Tree::reveal_step).
+    ///
+    /// Render again while this is `true` to finish revealing them.
+    #[must_use]
+    pub fn is_revealing(&self) -> bool {
+        !self.revealing.is_empty()
+    }
+
     /// Selects the given identifier.
     ///
+    /// With [`Tree::open_on_select`](crate::Tree::open_on_select) a newly selected node with children is opened.
+    ///
     /// Returns `true` when the selection changed.
     ///
     /// Clear the selection by passing an empty identifier vector:
@@ -78,25 +562,191 @@ impl TreeState {
         self.ensure_selected_in_view_on_next_render = true;
         let changed = self.selected != identifier;
         self.selected = identifier;
+        if changed && self.last_open_on_select {
+            let has_children = self
+                .selected_index()
+                .is_some_and(|index| self.last_has_children[index]);
+            if has_children {
+                self.open(self.selected.clone());
+            }
+        }
         changed
     }
 
+    /// Restore a selection, for example one persisted from [`selected`](Self::selected).
+    ///
+    /// Same as [`select`](Self::select) including scrolling it into view on next render.
+    pub fn set_selected(&mut self, identifier: Vec<u64>) {
+        self.select(identifier);
+    }
+
     /// Open a tree node.
+    /// Opening a node without children on last render makes it available with [`take_pending_open`](Self::take_pending_open).
+    ///
     /// Returns `true` when it was closed and has been opened.
     /// Returns `false` when it was already open.
     pub fn open(&mut self, identifier: Vec<u64>) -> bool {
         if identifier.is_empty() {
             false
         } else {
-            self.opened.insert(identifier)
+            let is_empty = self
+                .last_index(&identifier)
+                .is_some_and(|index| !self.last_has_children[index]);
+            if is_empty {
+                self.pending_open = Some(identifier.clone());
+            }
+            let changed = self.insert_opened(identifier.clone());
+            if changed {
+                self.revealing.insert(identifier, 0);
+            }
+            changed
+        }
+    }
+
+    /// Limit the amount of open nodes.
+    ///
+    /// Opening a node beyond the limit closes the least recently opened one.
+    /// This keeps the open nodes bounded for long running sessions with frequently rebuilt items.
+    /// Every way of opening nodes counts, including [`set_opened`](Self::set_opened), [`open_all_under`](Self::open_all_under) and nodes which were already open when the limit is set.
+    /// As their order is unknown, nodes which were already open or replaced via [`set_opened`](Self::set_opened) are closed from the deepest to the top level.
+    /// The ancestors of the node being opened are never closed so it stays visible, even when this exceeds the limit.
+    /// `None` removes the limit, which is the default.
+    pub fn set_open_limit(&mut self, limit: Option<usize>) {
+        self.open_limit = limit;
+        self.open_order.clear();
+        self.track_opened();
+    }
+
+    /// Open the node and track it for the [open limit](Self::set_open_limit).
+    ///
+    /// Returns `true` when it was closed and has been opened.
+    fn insert_opened(&mut self, identifier: Vec<u64>) -> bool {
+        if self.opened.contains(&identifier) {
+            return false;
+        }
+        if let Some(limit) = self.open_limit {
+            self.open_order.push_back(identifier.clone());
+            self.opened.insert(identifier.clone());
+            self.evict_opened(limit, &identifier);
+        } else {
+            self.opened.insert(identifier);
+        }
+        true
+    }
+
+    /// Track open nodes which are not tracked yet for the [open limit](Self::set_open_limit) and apply it.
+    fn track_opened(&mut self) {
+        let Some(limit) = self.open_limit else {
+            self.open_order.clear();
+            return;
+        };
+        self.open_order
+            .retain(|identifier| self.opened.contains(identifier));
+        let tracked = self.open_order.iter().collect::<HashSet<_>>();
+        let mut untracked = self
+            .opened
+            .iter()
+            .filter(|identifier| !tracked.contains(identifier))
+            .cloned()
+            .collect::<Vec<_>>();
+        // Their order is unknown so the deepest ones are closed first
+        untracked.sort_unstable_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        self.open_order.extend(untracked);
+        self.evict_opened(limit, &[]);
+    }
+
+    /// Close the least recently opened nodes until at most `limit` remain.
+    ///
+    /// The `opening` node and its ancestors are kept open.
+    fn evict_opened(&mut self, limit: usize, opening: &[u64]) {
+        // Forget nodes closed in the meantime
+        self.open_order
+            .retain(|identifier| self.opened.contains(identifier));
+        let mut index = 0;
+        while self.open_order.len() > limit && index < self.open_order.len() {
+            if opening.starts_with(&self.open_order[index]) {
+                index += 1;
+                continue;
+            }
+            if let Some(oldest) = self.open_order.remove(index) {
+                self.revealing.remove(&oldest);
+                self.opened.remove(&oldest);
+            }
+        }
+    }
+
+    /// Take the most recently opened node which had no children on last render.
+    ///
+    /// This allows to load children lazily, for example from a file system:
+    /// After handling the input check for a pending open node, load its children and rebuild the items with them.
+    /// The rebuilt node stays open as the open state is kept by identifier and not by the [`TreeItem`] itself.
+    ///
+    /// ```
+    /// # use managarr_tree_widget::{TreeItem, TreeState};
+    /// # let mut items = vec![TreeItem::new_leaf("folder")];
+    /// # let mut state = TreeState::default();
+    /// if let Some(identifier) = state.take_pending_open() {
+    ///     // Load the children of the identifier and rebuild the items
+    /// }
+    /// ```
+    pub fn take_pending_open(&mut self) -> Option<Vec<u64>> {
+        self.pending_open.take()
+    }
+
+    /// Open a tree node and close all of its siblings including everything below them.
+    ///
+    /// This allows for an accordion like behaviour where only one branch per level is open.
+    /// The siblings are the children of the parent within the `items`.
+    ///
+    /// Returns `true` when the open state of any node changed.
+    pub fn open_exclusive<T>(&mut self, items: &[TreeItem<T>], identifier: Vec<u64>) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let Some((_, parent)) = identifier.split_last() else {
+            return false;
+        };
+        let siblings = if parent.is_empty() {
+            items
+        } else {
+            match get_item(items, parent) {
+                Some(parent) => parent.children.as_slice(),
+                None => return false,
+            }
+        };
+
+        let before = self.opened.len();
+        for sibling in siblings {
+            let mut sibling_identifier = parent.to_vec();
+            sibling_identifier.push(sibling.identifier);
+            if sibling_identifier != identifier {
+                self.opened
+                    .retain(|open| !open.starts_with(&sibling_identifier));
+            }
         }
+        let closed_any = before != self.opened.len();
+        self.forget_closed();
+        self.open(identifier) || closed_any
     }
 
     /// Close a tree node.
     /// Returns `true` when it was open and has been closed.
     /// Returns `false` when it was already closed.
     pub fn close(&mut self, identifier: &[u64]) -> bool {
-        self.opened.remove(identifier)
+        let changed = self.opened.remove(identifier);
+        if changed {
+            self.revealing.remove(identifier);
+            self.open_order.retain(|open| open != identifier);
+        }
+        changed
+    }
+
+    /// Forget the reveal progress and open order of nodes which are no longer open.
+    fn forget_closed(&mut self) {
+        self.revealing
+            .retain(|identifier, _| self.opened.contains(identifier));
+        self.open_order
+            .retain(|identifier| self.opened.contains(identifier));
     }
 
     /// Toggles a tree node open/close state.
@@ -126,50 +776,524 @@ impl TreeState {
 
         self.ensure_selected_in_view_on_next_render = true;
 
-        // Reimplement self.close because of multiple different borrows
-        let was_open = self.opened.remove(&self.selected);
-        if was_open {
-            return true;
+        if self.opened.contains(&self.selected) {
+            return self.close(&self.selected.clone());
         }
 
         self.open(self.selected.clone())
     }
 
-    /// Closes all open nodes.
+    /// Adds the currently selected tree node to the multi-selection or removes it when it is already part of it.
     ///
-    /// Returns `true` when any node was closed.
-    pub fn close_all(&mut self) -> bool {
-        if self.opened.is_empty() {
-            false
-        } else {
-            self.opened.clear();
-            true
+    /// The multi-selection is independent of the single selection which keeps moving as before.
+    ///
+    /// Returns `true` when the multi-selection changed.
+    /// As toggle always changes something, this only returns `false` when nothing is selected.
+    pub fn toggle_selected_multi(&mut self) -> bool {
+        if self.selected.is_empty() {
+            return false;
+        }
+
+        if !self.multi_selected.remove(&self.selected) {
+            self.multi_selected.insert(self.selected.clone());
         }
+        true
     }
 
-    /// Select the first node.
+    /// Adds every visible item between the current selection and the `target` to the multi-selection.
     ///
-    /// Returns `true` when the selection changed.
-    pub fn select_first(&mut self) -> bool {
-        let identifier = self.last_identifiers.first().cloned().unwrap_or_default();
-        self.select(identifier)
+    /// Both ends are included and it does not matter whether `target` is above or below the selection.
+    /// Items within closed nodes are not visible and are therefore not added.
+    /// The selection itself stays where it is and remains the anchor for further ranges.
+    ///
+    /// Returns `true` when the multi-selection changed.
+    /// Returns `false` when the selection or the `target` is not visible.
+    pub fn select_range_to<T>(&mut self, items: &[TreeItem<T>], target: &[u64]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let visible = self.flatten(items);
+        let position = |identifier: &[u64]| {
+            visible
+                .iter()
+                .position(|flattened| flattened.identifier == identifier)
+        };
+        let (Some(anchor), Some(target)) = (position(&self.selected), position(target)) else {
+            return false;
+        };
+
+        let mut changed = false;
+        for flattened in &visible[anchor.min(target)..=anchor.max(target)] {
+            changed |= self.multi_selected.insert(flattened.identifier.clone());
+        }
+        changed
     }
 
-    /// Select the last node.
+    /// Adds every row rendered between two screen positions on last render to the multi-selection.
     ///
-    /// Returns `true` when the selection changed.
-    pub fn select_last(&mut self) -> bool {
-        let new_identifier = self.last_identifiers.last().cloned().unwrap_or_default();
-        self.select(new_identifier)
+    /// This is meant for dragging the mouse across rows.
+    /// Both rows are included and it does not matter which position is above the other.
+    /// Only the row (`y`) of the positions matters.
+    /// Positions above or below the rendered rows are clamped to the first or last rendered row.
+    ///
+    /// Returns `true` when the multi-selection changed.
+    /// Returns `false` when nothing was rendered.
+    pub fn select_drag(&mut self, from: Position, to: Position) -> bool {
+        let top = self
+            .last_rendered
+            .iter()
+            .map(|rendered| rendered.area.top())
+            .min();
+        let bottom = self
+            .last_rendered
+            .iter()
+            .map(|rendered| rendered.area.bottom())
+            .max();
+        let (Some(top), Some(bottom)) = (top, bottom) else {
+            return false;
+        };
+        // Items starting at the bottom are rendered upwards so the rows are looked up by their area
+        let row_index = |position: Position| {
+            let y = position.y.clamp(top, bottom.saturating_sub(1).max(top));
+            self.last_rendered
+                .iter()
+                .position(|rendered| (rendered.area.top()..rendered.area.bottom()).contains(&y))
+        };
+        let (Some(from), Some(to)) = (row_index(from), row_index(to)) else {
+            return false;
+        };
+
+        let mut changed = false;
+        for rendered in &self.last_rendered[from.min(to)..=from.max(to)] {
+            changed |= self.multi_selected.insert(rendered.identifier.clone());
+        }
+        changed
     }
 
-    /// Select the node on the given index.
-    ///
-    /// Returns `true` when the selection changed.
+    /// Clears the multi-selection.
     ///
-    /// This can be useful for mouse clicks.
-    #[deprecated = "Prefer self.click_at or self.rendered_at as visible index is hard to predict with height != 1"]
-    pub fn select_visible_index(&mut self, new_index: usize) -> bool {
+    /// Returns `true` when anything was part of the multi-selection.
+    pub fn clear_multi_selection(&mut self) -> bool {
+        if self.multi_selected.is_empty() {
+            false
+        } else {
+            self.multi_selected.clear();
+            true
+        }
+    }
+
+    /// Toggles the currently selected tree node together with all nodes below it.
+    ///
+    /// When the selected node is closed it is opened with all of its descendants.
+    /// When it is open the whole subtree is closed.
+    ///
+    /// Returns `true` when any node was opened / closed.
+    /// Returns `false` when nothing is selected or the selected node has no children in the `items`.
+    pub fn toggle_selected_recursive<T>(&mut self, items: &[TreeItem<T>]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        if self.selected.is_empty() {
+            return false;
+        }
+
+        self.ensure_selected_in_view_on_next_render = true;
+        let open = !self.opened.contains(&self.selected);
+        self.set_open_all_under(items, open) > 0
+    }
+
+    /// Closes all open nodes.
+    ///
+    /// Returns `true` when any node was closed.
+    pub fn close_all(&mut self) -> bool {
+        if self.opened.is_empty() {
+            false
+        } else {
+            self.opened.clear();
+            self.forget_closed();
+            true
+        }
+    }
+
+    /// Closes all nodes except the ancestors of the selected node.
+    ///
+    /// Only ancestors which exist in the `items` stay open.
+    /// When nothing is selected all nodes are closed.
+    ///
+    /// Returns `true` when the open nodes changed.
+    pub fn collapse_all_except_selected<T>(&mut self, items: &[TreeItem<T>]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let mut opened = HashSet::new();
+        let mut current = items;
+        for (depth, identifier) in self
+            .selected
+            .iter()
+            .enumerate()
+            .take(self.selected.len().saturating_sub(1))
+        {
+            let Some(item) = current.iter().find(|item| item.identifier() == *identifier) else {
+                break;
+            };
+            opened.insert(self.selected[..=depth].to_vec());
+            current = item.children();
+        }
+
+        if opened == self.opened {
+            false
+        } else {
+            self.set_opened(opened);
+            true
+        }
+    }
+
+    /// Forget everything referring to nodes which no longer exist in the `items`.
+    ///
+    /// This removes open, multi-selected and checked nodes and clears the selection when it no longer exists.
+    /// Useful after rebuilding the items, for example before persisting the state.
+    ///
+    /// Returns `true` when anything was removed.
+    pub fn prune<T>(&mut self, items: &[TreeItem<T>]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let exists = |identifier: &Vec<u64>| get_item(items, identifier).is_some();
+        let before = self.opened.len() + self.multi_selected.len() + self.checked.len();
+        self.opened.retain(exists);
+        self.multi_selected.retain(exists);
+        self.checked.retain(exists);
+        self.forget_closed();
+        let removed = before != self.opened.len() + self.multi_selected.len() + self.checked.len();
+
+        let selection_removed = !self.selected.is_empty() && !exists(&self.selected);
+        if selection_removed {
+            self.selected.clear();
+        }
+        removed || selection_removed
+    }
+
+    /// Opens the selected node and all nodes below it.
+    /// When nothing is selected all nodes of the `items` are opened.
+    ///
+    /// Returns the amount of nodes which were closed and have been opened.
+    pub fn open_all_under<T>(&mut self, items: &[TreeItem<T>]) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.set_open_all_under(items, true)
+    }
+
+    /// Closes the selected node and all nodes below it.
+    /// When nothing is selected all nodes of the `items` are closed.
+    ///
+    /// Returns the amount of nodes which were open and have been closed.
+    pub fn close_all_under<T>(&mut self, items: &[TreeItem<T>]) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.set_open_all_under(items, false)
+    }
+
+    /// Opens all nodes on the given `depth`. Depth 0 are the top level items.
+    ///
+    /// Returns the amount of nodes which were opened.
+    pub fn open_level<T>(&mut self, items: &[TreeItem<T>], depth: usize) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.set_open_level(items, depth, true)
+    }
+
+    /// Closes all nodes on the given `depth`. Depth 0 are the top level items.
+    ///
+    /// Returns the amount of nodes which were closed.
+    pub fn close_level<T>(&mut self, items: &[TreeItem<T>], depth: usize) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.set_open_level(items, depth, false)
+    }
+
+    fn set_open_level<T>(&mut self, items: &[TreeItem<T>], depth: usize, open: bool) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let mut changed = 0;
+        for (identifier, item) in iter_all(items) {
+            if identifier.len() != depth + 1 || item.is_leaf() {
+                continue;
+            }
+            let has_changed = if open {
+                self.open(identifier)
+            } else {
+                self.close(&identifier)
+            };
+            if has_changed {
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    fn set_open_all_under<T>(&mut self, items: &[TreeItem<T>], open: bool) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let changed = if self.selected.is_empty() {
+            self.set_open_recursive(items, &mut Vec::new(), open)
+        } else if let Some(item) = get_item(items, &self.selected) {
+            let mut identifier = self.selected.clone();
+            identifier.pop();
+            self.set_open_recursive(std::slice::from_ref(item), &mut identifier, open)
+        } else {
+            0
+        };
+        if !open {
+            self.forget_closed();
+        }
+        changed
+    }
+
+    /// Opens or closes all `items` with children below the `parent` identifier.
+    fn set_open_recursive<T>(
+        &mut self,
+        items: &[TreeItem<T>],
+        parent: &mut Vec<u64>,
+        open: bool,
+    ) -> usize
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let mut changed = 0;
+        for item in items.iter().filter(|item| item.has_children()) {
+            parent.push(item.identifier);
+            let was_changed = if open {
+                self.insert_opened(parent.clone())
+            } else {
+                self.opened.remove(parent)
+            };
+            changed += usize::from(was_changed);
+            changed += self.set_open_recursive(&item.children, parent, open);
+            parent.pop();
+        }
+        changed
+    }
+
+    /// Depth, index among its siblings and amount of siblings (including itself) of the selected node.
+    ///
+    /// Returns `None` when nothing is selected or the selection does not exist in the `items`.
+    #[must_use]
+    pub fn selected_position<T>(&self, items: &[TreeItem<T>]) -> Option<(usize, usize, usize)>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let (last, ancestors) = self.selected.split_last()?;
+        let siblings = if ancestors.is_empty() {
+            items
+        } else {
+            get_item(items, ancestors)?.children()
+        };
+        let index = siblings
+            .iter()
+            .position(|item| item.identifier() == *last)?;
+        Some((ancestors.len(), index, siblings.len()))
+    }
+
+    /// Labels of the selected node and all its ancestors joined with the `separator`.
+    ///
+    /// The labels are converted to plain text, so any styling is dropped.
+    /// Returns `None` when nothing is selected or the selection does not exist in the `items`.
+    #[must_use]
+    pub fn selected_breadcrumb<T>(&self, items: &[TreeItem<T>], separator: &str) -> Option<String>
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        if self.selected.is_empty() {
+            return None;
+        }
+        let mut siblings = items;
+        let mut labels = Vec::with_capacity(self.selected.len());
+        for identifier in &self.selected {
+            let item = siblings
+                .iter()
+                .find(|item| item.identifier() == *identifier)?;
+            labels.push(item.plain_text());
+            siblings = item.children();
+        }
+        Some(labels.join(separator))
+    }
+
+    /// Select the parent of the currently selected node.
+    ///
+    /// Unlike [`key_left`](Self::key_left) this does not close the selected node first.
+    ///
+    /// Returns `true` when the selection changed.
+    /// Returns `false` when nothing or a top level node is selected or the parent does not exist in the `items`.
+    pub fn select_parent<T>(&mut self, items: &[TreeItem<T>]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let Some((_, parent)) = self.selected.split_last() else {
+            return false;
+        };
+        if parent.is_empty() || get_item(items, parent).is_none() {
+            return false;
+        }
+        self.select(parent.to_vec())
+    }
+
+    /// Select the next sibling of the currently selected node, skipping over its children.
+    ///
+    /// Siblings which are not [selectable](TreeItem::selectable) are skipped.
+    ///
+    /// Returns `true` when the selection changed.
+    /// Returns `false` when nothing or the last sibling is selected.
+    pub fn select_next_sibling<T>(&mut self, items: &[TreeItem<T>]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.select_sibling(items, true)
+    }
+
+    /// Select the previous sibling of the currently selected node, skipping over the children of that sibling.
+    ///
+    /// Siblings which are not [selectable](TreeItem::selectable) are skipped.
+    ///
+    /// Returns `true` when the selection changed.
+    /// Returns `false` when nothing or the first sibling is selected.
+    pub fn select_prev_sibling<T>(&mut self, items: &[TreeItem<T>]) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.select_sibling(items, false)
+    }
+
+    fn select_sibling<T>(&mut self, items: &[TreeItem<T>], next: bool) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let Some((current, parent)) = self.selected.split_last() else {
+            return false;
+        };
+        let siblings = if parent.is_empty() {
+            items
+        } else {
+            match get_item(items, parent) {
+                Some(parent) => parent.children.as_slice(),
+                None => return false,
+            }
+        };
+        let Some(index) = siblings
+            .iter()
+            .position(|sibling| sibling.identifier == *current)
+        else {
+            return false;
+        };
+
+        let sibling = if next {
+            siblings[index + 1..]
+                .iter()
+                .find(|sibling| sibling.selectable)
+        } else {
+            siblings[..index]
+                .iter()
+                .rev()
+                .find(|sibling| sibling.selectable)
+        };
+        let Some(sibling) = sibling else {
+            return false;
+        };
+
+        let mut identifier = parent.to_vec();
+        identifier.push(sibling.identifier);
+        self.select(identifier)
+    }
+
+    /// Select the next visible item whose label starts with the `query`, case-insensitively.
+    ///
+    /// The search starts after the current selection and wraps around at the end.
+    /// Labels are compared as plain text, styled spans are flattened and their styling is ignored.
+    /// Items which are not [selectable](TreeItem::selectable) are skipped.
+    ///
+    /// Returns `true` when a match was found.
+    pub fn search_forward<T>(&mut self, items: &[TreeItem<T>], query: &str) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.search(items, query, true)
+    }
+
+    /// Select the previous visible item whose label starts with the `query`, case-insensitively.
+    ///
+    /// Same as [`search_forward`](Self::search_forward) but searches upwards and wraps around at the start.
+    pub fn search_backward<T>(&mut self, items: &[TreeItem<T>], query: &str) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        self.search(items, query, false)
+    }
+
+    fn search<T>(&mut self, items: &[TreeItem<T>], query: &str, forward: bool) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        if query.is_empty() {
+            return false;
+        }
+        let query = query.to_lowercase();
+        let visible = self.flatten(items);
+        let len = visible.len();
+        let current = visible
+            .iter()
+            .position(|flattened| flattened.identifier == self.selected);
+
+        // Every other item in search direction, the current selection is checked last
+        let order = (1..=len).map(|step| match (current, forward) {
+            (Some(current), true) => (current + step) % len,
+            (Some(current), false) => (current + len - step) % len,
+            (None, true) => step - 1,
+            (None, false) => len - step,
+        });
+        let found = order.map(|index| &visible[index]).find(|flattened| {
+            flattened.item.selectable
+                && flattened
+                    .item
+                    .plain_text()
+                    .to_lowercase()
+                    .starts_with(&query)
+        });
+        let Some(flattened) = found else {
+            return false;
+        };
+        let identifier = flattened.identifier.clone();
+        self.select(identifier);
+        true
+    }
+
+    /// Select the first selectable node visible on last render.
+    ///
+    /// Returns `true` when the selection changed.
+    /// When nothing was visible the selection is cleared and `false` is returned.
+    pub fn select_first(&mut self) -> bool {
+        self.select_towards(0, true)
+    }
+
+    /// Select the last selectable node visible on last render.
+    ///
+    /// Returns `true` when the selection changed.
+    /// When nothing was visible the selection is cleared and `false` is returned.
+    pub fn select_last(&mut self) -> bool {
+        self.select_towards(usize::MAX, false)
+    }
+
+    /// Select the node on the given index.
+    ///
+    /// Returns `true` when the selection changed.
+    ///
+    /// This can be useful for mouse clicks.
+    #[deprecated = "Prefer self.click_at or self.rendered_at as visible index is hard to predict with height != 1"]
+    pub fn select_visible_index(&mut self, new_index: usize) -> bool {
         let new_index = new_index.min(self.last_biggest_index);
         let new_identifier = self
             .last_identifiers
@@ -179,6 +1303,24 @@ impl TreeState {
         self.select(new_identifier)
     }
 
+    /// Select the node on the given index of the currently viewable items, see [`flatten`](Self::flatten).
+    ///
+    /// An index past the end selects the last viewable item.
+    /// Only the open nodes are taken into account, not what was rendered, so a [`Tree::filter`](crate::Tree::filter) is ignored.
+    /// For mouse clicks prefer [`click_at`](Self::click_at) as the index does not match the row with a height != 1.
+    ///
+    /// Returns `true` when the selection changed.
+    pub fn select_visible_index_in<T>(&mut self, items: &[TreeItem<T>], index: usize) -> bool
+    where
+        T: ToText + Clone + Default + Display + Hash + PartialEq + Eq,
+    {
+        let visible = self.flatten(items);
+        let Some(flattened) = visible.get(index).or_else(|| visible.last()) else {
+            return false;
+        };
+        self.select(flattened.identifier.clone())
+    }
+
     /// Move the current selection with the direction/amount by the given function.
     ///
     /// Returns `true` when the selection changed.
@@ -246,6 +1388,95 @@ impl TreeState {
         self.select(new_identifier)
     }
 
+    /// Move the selection up by the given amount of lines.
+    ///
+    /// Each visible [`TreeItem`] takes as many lines as its height on last render.
+    /// The selection moves by at least one item and stops at the first item.
+    ///
+    /// Returns `true` when the selection changed.
+    pub fn select_page_up(&mut self, lines: usize) -> bool {
+        let new_index = self.selected_index().map_or(usize::MAX, |current| {
+            let mut new_index = current.saturating_sub(1);
+            let mut height = 0;
+            for (index, item_height) in self.last_heights.iter().enumerate().take(current).rev() {
+                height += item_height;
+                if height > lines {
+                    break;
+                }
+                new_index = index;
+            }
+            new_index
+        });
+        self.select_towards(new_index, false)
+    }
+
+    /// Move the selection down by the given amount of lines.
+    ///
+    /// Each visible [`TreeItem`] takes as many lines as its height on last render.
+    /// The selection moves by at least one item and stops at the last item.
+    ///
+    /// Returns `true` when the selection changed.
+    pub fn select_page_down(&mut self, lines: usize) -> bool {
+        let new_index = self.selected_index().map_or(0, |current| {
+            let mut new_index = current.saturating_add(1);
+            let mut height = 0;
+            for (index, item_height) in self.last_heights.iter().enumerate().skip(current + 1) {
+                height += item_height;
+                if height > lines {
+                    break;
+                }
+                new_index = index;
+            }
+            new_index
+        });
+        self.select_towards(new_index, true)
+    }
+
+    /// Index of the identifier within the identifiers visible on last render.
+    fn last_index(&self, identifier: &[u64]) -> Option<usize> {
+        self.last_indices.get(identifier).copied()
+    }
+
+    /// Index of the selection within the identifiers visible on last render.
+    fn selected_index(&self) -> Option<usize> {
+        self.last_index(&self.selected)
+    }
+
+    /// Select the selectable item visible on last render closest to the given index.
+    ///
+    /// Items in the direction of movement (`down` or up) are preferred.
+    /// Otherwise the closest one between the current selection and the index is used.
+    /// When there is none the selection stays unless it is not selectable itself.
+    fn select_towards(&mut self, index: usize, down: bool) -> bool {
+        let len = self.last_identifiers.len();
+        if len == 0 {
+            self.select(Vec::new());
+            return false;
+        }
+
+        let index = index.min(len - 1);
+        let current = self.selected_index();
+        let is_selectable = |index: &usize| self.last_selectable[*index];
+        let found = if down {
+            let lower = current.map_or(0, |current| current + 1);
+            (index..len)
+                .find(is_selectable)
+                .or_else(|| (lower..index).rev().find(is_selectable))
+        } else {
+            let upper = current.unwrap_or(len);
+            (0..=index)
+                .rev()
+                .find(is_selectable)
+                .or_else(|| (index + 1..upper).find(is_selectable))
+        };
+
+        match found {
+            Some(index) => self.select(self.last_identifiers[index].clone()),
+            None if current.is_some_and(|current| self.last_selectable[current]) => false,
+            None => self.select(Vec::new()),
+        }
+    }
+
     /// Get the identifier that was rendered for the given position on last render.
     #[must_use]
     pub fn rendered_at(&self, position: Position) -> Option<&[u64]> {
@@ -253,11 +1484,46 @@ impl TreeState {
             return None;
         }
 
-        self.last_rendered_identifiers
+        self.last_rendered
+            .iter()
+            .find(|rendered| rendered.area.contains(position))
+            .map(|rendered| rendered.identifier.as_ref())
+    }
+
+    /// Get which part of the item rendered at the given position on last render was hit.
+    ///
+    /// This allows toggling an item when its open / close symbol is clicked while selecting it otherwise.
+    /// Items without children never report [`ToggleHit::Symbol`].
+    #[must_use]
+    pub fn toggle_target_at(&self, position: Position) -> Option<ToggleHit> {
+        if !self.last_area.contains(position) {
+            return None;
+        }
+
+        let rendered = self
+            .last_rendered
             .iter()
-            .rev()
-            .find(|(y, _)| position.y >= *y)
-            .map(|(_, identifier)| identifier.as_ref())
+            .find(|rendered| rendered.area.contains(position))?;
+        let on_symbol = rendered
+            .symbol
+            .as_ref()
+            .is_some_and(|symbol| position.y == rendered.area.y && symbol.contains(&position.x));
+        Some(if on_symbol {
+            ToggleHit::Symbol
+        } else {
+            ToggleHit::Label
+        })
+    }
+
+    /// Get the area the given identifier was rendered into on last render.
+    ///
+    /// Returns `None` when it was not rendered (for example scrolled out of view).
+    #[must_use]
+    pub fn rendered_area(&self, identifier: &[u64]) -> Option<Rect> {
+        self.last_rendered
+            .iter()
+            .find(|rendered| rendered.identifier == identifier)
+            .map(|rendered| rendered.area)
     }
 
     /// Select what was rendered at the given position on last render.
@@ -277,11 +1543,49 @@ impl TreeState {
         }
     }
 
-    /// Ensure the selected [`TreeItem`] is in view on next render
-    pub fn scroll_selected_into_view(&mut self) {
+    /// Toggle what was rendered at the given position on last render when it has children.
+    /// Select and [`open`](Self::open) it otherwise so its children can be loaded with [`take_pending_open`](Self::take_pending_open).
+    ///
+    /// Meant for double clicks, the detection of which is left to the application.
+    pub fn toggle_at(&mut self, position: Position) -> ToggleAction {
+        let Some(identifier) = self.rendered_at(position).map(<[u64]>::to_vec) else {
+            return ToggleAction::Nothing;
+        };
+        let has_children = self
+            .last_index(&identifier)
+            .is_some_and(|index| self.last_has_children[index]);
+        if has_children {
+            self.toggle(identifier);
+            ToggleAction::Toggled
+        } else {
+            self.select(identifier.clone());
+            self.open(identifier);
+            ToggleAction::Selected
+        }
+    }
+
+    /// Ensure the selected [`TreeItem`] is in view on next render.
+    ///
+    /// Useful after changing the selection without the methods of the `TreeState`, which already do this.
+    /// The request is consumed by the next render, later renders keep the offset as it is.
+    pub fn ensure_selected_in_view(&mut self) {
         self.ensure_selected_in_view_on_next_render = true;
     }
 
+    /// Ensure the selected [`TreeItem`] is in view on next render.
+    #[deprecated = "Use self.ensure_selected_in_view()"]
+    pub fn scroll_selected_into_view(&mut self) {
+        self.ensure_selected_in_view();
+    }
+
+    /// Reveal the [`TreeItem`] of the given identifier on next render without selecting it.
+    ///
+    /// All of its ancestors are opened and the view is scrolled so it is visible.
+    /// When the identifier does not exist in the items on next render nothing happens.
+    pub fn scroll_to(&mut self, identifier: Vec<u64>) {
+        self.scroll_to_on_next_render = Some(identifier);
+    }
+
     /// Scroll the specified amount of lines up
     ///
     /// Returns `true` when the scroll position changed.
@@ -305,55 +1609,1441 @@ impl TreeState {
         before != self.offset
     }
 
+    /// Scroll the view by the given amount of items, down when positive and up when negative.
+    ///
+    /// Meant for mouse wheels. The selection is not moved, unlike [`select_relative`](Self::select_relative).
+    /// The offset is clamped using the last render so the last page stays completely filled.
+    ///
+    /// Returns `true` when the scroll position changed.
+    pub fn scroll_by(&mut self, delta: i32) -> bool {
+        let max_offset = max_offset(&self.last_heights, self.last_area.height as usize);
+        let before = self.offset;
+        let offset = self.offset.min(max_offset);
+        let lines = delta.unsigned_abs() as usize;
+        self.offset = if delta < 0 {
+            offset.saturating_sub(lines)
+        } else {
+            offset.saturating_add(lines).min(max_offset)
+        };
+        before != self.offset
+    }
+
+    /// Scroll the labels the specified amount of columns to the left.
+    /// The indentation and symbols stay in place.
+    ///
+    /// Returns `true` when the horizontal scroll position changed.
+    /// Returns `false` when the labels are already shown from their start.
+    pub fn scroll_left(&mut self, columns: usize) -> bool {
+        let before = self.horizontal_scroll;
+        self.horizontal_scroll = self.horizontal_scroll.saturating_sub(columns);
+        before != self.horizontal_scroll
+    }
+
+    /// Scroll the labels the specified amount of columns to the right.
+    /// The indentation and symbols stay in place.
+    ///
+    /// Returns `true` when the horizontal scroll position changed.
+    /// Returns `false` when only the last column of the widest label rendered on last render is still shown.
+    pub fn scroll_right(&mut self, columns: usize) -> bool {
+        let before = self.horizontal_scroll;
+        self.horizontal_scroll = self
+            .horizontal_scroll
+            .saturating_add(columns)
+            .min(self.last_max_horizontal_scroll);
+        before != self.horizontal_scroll
+    }
+
     /// Handles the up arrow key.
     /// Moves up in the current depth or to its parent.
+    /// Items which are not [selectable](TreeItem::selectable) are skipped.
+    /// At the first item it stays there unless [`Tree::wrap_navigation`](crate::Tree::wrap_navigation) selects the last one.
     ///
     /// Returns `true` when the selection changed.
     pub fn key_up(&mut self) -> bool {
-        self.select_relative(|current| {
-            // When nothing is selected, fall back to end
-            current.map_or(usize::MAX, |current| current.saturating_sub(1))
-        })
+        let current = self.selected_index();
+        if self.last_wrap_navigation
+            && current.is_some_and(|current| !self.last_selectable[..current].contains(&true))
+        {
+            return self.select_towards(usize::MAX, false);
+        }
+        // When nothing is selected, fall back to end
+        let new_index = current.map_or(usize::MAX, |current| current.saturating_sub(1));
+        self.select_towards(new_index, false)
     }
 
     /// Handles the down arrow key.
     /// Moves down in the current depth or into a child node.
+    /// Items which are not [selectable](TreeItem::selectable) are skipped.
+    /// At the last item it stays there unless [`Tree::wrap_navigation`](crate::Tree::wrap_navigation) selects the first one.
     ///
     /// Returns `true` when the selection changed.
     pub fn key_down(&mut self) -> bool {
-        self.select_relative(|current| {
-            // When nothing is selected, fall back to start
-            current.map_or(0, |current| current.saturating_add(1))
-        })
+        let current = self.selected_index();
+        if self.last_wrap_navigation
+            && current.is_some_and(|current| !self.last_selectable[current + 1..].contains(&true))
+        {
+            return self.select_towards(0, true);
+        }
+        // When nothing is selected, fall back to start
+        let new_index = current.map_or(0, |current| current.saturating_add(1));
+        self.select_towards(new_index, true)
     }
 
     /// Handles the left arrow key.
-    /// Closes the currently selected or moves to its parent.
+    /// Closes the currently selected when it is open and has children.
+    /// Otherwise moves to its parent.
     ///
     /// Returns `true` when the selection or the open state changed.
     pub fn key_left(&mut self) -> bool {
         self.ensure_selected_in_view_on_next_render = true;
-        // Reimplement self.close because of multiple different borrows
-        let mut changed = self.opened.remove(&self.selected);
-        if !changed {
+        let has_children = self
+            .selected_index()
+            .is_none_or(|index| self.last_has_children[index]);
+        if has_children && self.opened.contains(&self.selected) {
+            self.close(&self.selected.clone())
+        } else {
             // Select the parent by removing the leaf from selection
-            let popped = self.selected.pop();
-            changed = popped.is_some();
+            self.selected.pop().is_some()
         }
-        changed
     }
 
     /// Handles the right arrow key.
-    /// Opens the currently selected.
+    /// Opens the currently selected when it is closed.
+    /// Nodes without children are opened too so their children can be loaded with [`take_pending_open`](Self::take_pending_open).
+    /// Otherwise moves to its first selectable child.
     ///
-    /// Returns `true` when it was closed and has been opened.
-    /// Returns `false` when it was already open or nothing being selected.
+    /// Returns `true` when the selection or the open state changed.
+    /// Returns `false` when nothing is selected, it is already open without children or none of them is selectable.
     pub fn key_right(&mut self) -> bool {
         if self.selected.is_empty() {
-            false
-        } else {
+            return false;
+        }
+
+        let Some(index) = self.selected_index() else {
+            // Not visible on last render so there is nothing known about its children
             self.ensure_selected_in_view_on_next_render = true;
-            self.open(self.selected.clone())
+            return self.open(self.selected.clone());
+        };
+        self.ensure_selected_in_view_on_next_render = true;
+        if !self.last_has_children[index] || !self.opened.contains(&self.selected) {
+            return self.open(self.selected.clone());
         }
+
+        let depth = self.selected.len();
+        let child = self
+            .last_identifiers
+            .iter()
+            .enumerate()
+            .skip(index + 1)
+            .take_while(|(_, identifier)| identifier.starts_with(&self.selected))
+            .find(|(index, identifier)| {
+                identifier.len() == depth + 1 && self.last_selectable[*index]
+            });
+        match child {
+            Some((_, identifier)) => self.select(identifier.clone()),
+            None => false,
+        }
+    }
+}
+
+/// The biggest offset that still completely fills the last page, just like render.
+fn max_offset(heights: &[usize], available_height: usize) -> usize {
+    let mut max_offset = heights.len();
+    let mut last_page_height = 0;
+    while max_offset > 0 && last_page_height + heights[max_offset - 1] <= available_height {
+        max_offset -= 1;
+        last_page_height += heights[max_offset];
+    }
+    max_offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Corner;
+    use ratatui::buffer::Buffer;
+    use ratatui::widgets::StatefulWidget;
+
+    fn render(items: &[TreeItem<&'static str>], state: &mut TreeState) {
+        let tree = Tree::new(items).unwrap();
+        let area = Rect::new(0, 0, 20, 10);
+        let mut buffer = Buffer::empty(area);
+        StatefulWidget::render(tree, area, &mut buffer, state);
+    }
+
+    #[test]
+    fn select_first_and_last() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        assert!(state.select_last());
+        assert_eq!(state.selected(), [items[2].identifier()]);
+        assert!(!state.select_last());
+
+        assert!(state.select_first());
+        assert_eq!(state.selected(), [items[0].identifier()]);
+        assert!(!state.select_first());
+    }
+
+    #[test]
+    fn select_page_down_and_up() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        render(&items, &mut state);
+
+        assert!(state.select_page_down(3));
+        assert_eq!(state.selected(), [items[0].identifier()]);
+        assert!(state.select_page_down(3));
+        assert_eq!(
+            state.selected(),
+            [items[1].identifier(), items[1].children()[1].identifier()]
+        );
+        assert!(state.select_page_down(3));
+        assert_eq!(state.selected(), [items[2].identifier()]);
+        assert!(!state.select_page_down(3));
+
+        assert!(state.select_page_up(1));
+        assert_eq!(
+            state.selected(),
+            [items[1].identifier(), items[1].children()[2].identifier()]
+        );
+        assert!(state.select_page_up(10));
+        assert_eq!(state.selected(), [items[0].identifier()]);
+        assert!(!state.select_page_up(10));
+    }
+
+    #[test]
+    fn select_page_moves_at_least_one_item() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+        state.select_first();
+
+        assert!(state.select_page_down(0));
+        assert_eq!(state.selected(), [items[1].identifier()]);
+        assert!(state.select_page_up(0));
+        assert_eq!(state.selected(), [items[0].identifier()]);
+    }
+
+    #[test]
+    fn rendered_at_and_rendered_area() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        let bravo = [items[1].identifier()];
+        assert_eq!(
+            state.rendered_at(Position::new(5, 1)),
+            Some(bravo.as_slice())
+        );
+        assert_eq!(state.rendered_area(&bravo), Some(Rect::new(0, 1, 20, 1)));
+        assert_eq!(state.rendered_at(Position::new(5, 5)), None);
+        assert_eq!(state.rendered_at(Position::new(25, 1)), None);
+
+        render(&[], &mut state);
+        assert_eq!(state.rendered_at(Position::new(5, 1)), None);
+        assert_eq!(state.rendered_area(&bravo), None);
+    }
+
+    #[test]
+    fn toggle_target_at_symbol_or_label() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        // Alfa is a leaf, its (blank) symbol is never a hit
+        assert_eq!(
+            state.toggle_target_at(Position::new(0, 0)),
+            Some(ToggleHit::Label)
+        );
+        assert_eq!(
+            state.toggle_target_at(Position::new(0, 1)),
+            Some(ToggleHit::Symbol)
+        );
+        assert_eq!(
+            state.toggle_target_at(Position::new(1, 1)),
+            Some(ToggleHit::Symbol)
+        );
+        assert_eq!(
+            state.toggle_target_at(Position::new(2, 1)),
+            Some(ToggleHit::Label)
+        );
+        assert_eq!(state.toggle_target_at(Position::new(0, 5)), None);
+    }
+
+    #[test]
+    fn toggle_at_toggles_or_selects() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        assert_eq!(state.toggle_at(Position::new(5, 1)), ToggleAction::Toggled);
+        assert_eq!(state.opened(), &HashSet::from([vec![bravo]]));
+        assert!(state.selected().is_empty());
+
+        render(&items, &mut state);
+        assert_eq!(state.toggle_at(Position::new(5, 2)), ToggleAction::Selected);
+        let charlie = vec![bravo, items[1].children()[0].identifier()];
+        assert_eq!(state.selected(), charlie);
+        assert_eq!(state.take_pending_open(), Some(charlie));
+        assert_eq!(state.toggle_at(Position::new(5, 9)), ToggleAction::Nothing);
+
+        assert_eq!(state.toggle_at(Position::new(5, 1)), ToggleAction::Toggled);
+        assert!(!state.is_open(&[bravo]));
+    }
+
+    #[test]
+    fn toggle_selected_multi() {
+        let mut state = TreeState::default();
+        assert!(!state.toggle_selected_multi());
+
+        state.select(vec![1]);
+        assert!(state.toggle_selected_multi());
+        state.select(vec![2, 3]);
+        assert!(state.toggle_selected_multi());
+        assert_eq!(
+            state.selected_multi(),
+            &HashSet::from([vec![1], vec![2, 3]])
+        );
+
+        assert!(state.toggle_selected_multi());
+        assert_eq!(state.selected_multi(), &HashSet::from([vec![1]]));
+
+        assert!(state.clear_multi_selection());
+        assert!(!state.clear_multi_selection());
+        assert!(state.selected_multi().is_empty());
+        assert_eq!(state.selected(), [2, 3]);
+    }
+
+    #[test]
+    fn select_drag_from_the_bottom_corner() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        let tree = Tree::new(&items).unwrap().start_corner(Corner::BottomLeft);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 10));
+        StatefulWidget::render(tree, buffer.area, &mut buffer, &mut state);
+
+        // Alfa is on the bottom line, Charlie two lines above
+        assert!(state.select_drag(Position::new(0, 9), Position::new(0, 7)));
+        let expected = HashSet::from([
+            vec![items[0].identifier()],
+            vec![bravo],
+            vec![bravo, items[1].children()[0].identifier()],
+        ]);
+        assert_eq!(state.selected_multi(), &expected);
+
+        // Above the last row (Hotel) is clamped
+        assert!(state.select_drag(Position::new(0, 9), Position::new(0, 0)));
+        assert_eq!(state.selected_multi().len(), 6);
+    }
+
+    #[test]
+    fn select_range_to_follows_visible_order() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.select(vec![items[2].identifier()]);
+
+        // Delta is closed so Echo and Foxtrot are not part of the range
+        assert!(state.select_range_to(&items, &[bravo, delta]));
+        let expected = HashSet::from([
+            vec![bravo, delta],
+            vec![bravo, items[1].children()[2].identifier()],
+            vec![items[2].identifier()],
+        ]);
+        assert_eq!(state.selected_multi(), &expected);
+        assert!(!state.select_range_to(&items, &[bravo, delta]));
+
+        state.select(vec![items[0].identifier()]);
+        assert!(state.select_range_to(&items, &[bravo]));
+        assert_eq!(state.selected_multi().len(), 5);
+        assert_eq!(state.selected(), [items[0].identifier()]);
+
+        assert!(!state.select_range_to(&items, &[bravo, delta, 42]));
+    }
+
+    #[test]
+    fn max_visible_width_of_the_example() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        let tree = Tree::new(&items).unwrap();
+        // "▶ Bravo" and "  Hotel"
+        assert_eq!(state.max_visible_width(&tree), 7);
+
+        let counting = tree.clone().closed_child_count(true);
+        assert_eq!(state.max_visible_width(&counting), 11);
+
+        // 2 levels of indentation, symbol and "Foxtrot"
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![
+            items[1].identifier(),
+            items[1].children()[1].identifier(),
+        ]);
+        assert_eq!(state.max_visible_width(&tree), 13);
+
+        state.select(vec![items[0].identifier()]);
+        let tree = tree.highlight_symbol(">> ");
+        assert_eq!(state.max_visible_width(&tree), 16);
+    }
+
+    #[test]
+    fn select_drag_clamps_to_rendered_rows() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        assert!(!state.select_drag(Position::new(0, 0), Position::new(0, 5)));
+
+        state.open(vec![bravo]);
+        render(&items, &mut state);
+
+        // Dragging upwards from Delta to Charlie
+        assert!(state.select_drag(Position::new(5, 3), Position::new(0, 2)));
+        let expected = HashSet::from([
+            vec![bravo, items[1].children()[0].identifier()],
+            vec![bravo, items[1].children()[1].identifier()],
+        ]);
+        assert_eq!(state.selected_multi(), &expected);
+        assert!(!state.select_drag(Position::new(0, 2), Position::new(0, 3)));
+
+        // Below the last row (Hotel) is clamped
+        assert!(state.select_drag(Position::new(0, 4), Position::new(50, 50)));
+        assert_eq!(state.selected_multi().len(), 4);
+        assert!(state
+            .selected_multi()
+            .contains(&vec![items[2].identifier()]));
+    }
+
+    #[test]
+    fn navigation_skips_unselectable_items() {
+        let items = [
+            TreeItem::new_leaf("Header").selectable(false),
+            TreeItem::new_leaf("Alfa"),
+            TreeItem::new_leaf("Separator").selectable(false),
+            TreeItem::new_leaf("Bravo"),
+            TreeItem::new_leaf("Footer").selectable(false),
+        ];
+        let alfa = vec![items[1].identifier()];
+        let bravo = vec![items[3].identifier()];
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        assert!(state.key_down());
+        assert_eq!(state.selected(), alfa);
+        assert!(state.key_down());
+        assert_eq!(state.selected(), bravo);
+        assert!(!state.key_down());
+        assert_eq!(state.selected(), bravo);
+        assert!(state.key_up());
+        assert_eq!(state.selected(), alfa);
+        assert!(!state.key_up());
+        assert_eq!(state.selected(), alfa);
+
+        assert!(state.select_last());
+        assert_eq!(state.selected(), bravo);
+        assert!(state.select_first());
+        assert_eq!(state.selected(), alfa);
+        assert!(state.select_page_down(10));
+        assert_eq!(state.selected(), bravo);
+    }
+
+    #[test]
+    fn navigation_without_selectable_items_clears_selection() {
+        let items = [TreeItem::new_leaf("Header").selectable(false)];
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+        state.select(vec![items[0].identifier()]);
+
+        assert!(state.key_down());
+        assert!(state.selected().is_empty());
+        assert!(!state.select_first());
+        assert!(!state.key_up());
+        assert!(state.selected().is_empty());
+    }
+
+    #[test]
+    fn open_and_close_all_under_selected() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        state.select(vec![bravo]);
+
+        assert_eq!(state.open_all_under(&items), 2);
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![bravo], vec![bravo, delta]])
+        );
+        assert_eq!(state.open_all_under(&items), 0);
+
+        state.select(vec![bravo, delta]);
+        assert_eq!(state.close_all_under(&items), 1);
+        assert_eq!(state.opened(), &HashSet::from([vec![bravo]]));
+
+        state.select(vec![bravo, 42]);
+        assert_eq!(state.open_all_under(&items), 0);
+    }
+
+    #[test]
+    fn open_and_close_all_under_without_selection() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![42]);
+
+        assert_eq!(state.open_all_under(&items), 2);
+        assert_eq!(state.close_all_under(&items), 2);
+        assert_eq!(state.opened(), &HashSet::from([vec![42]]));
+    }
+
+    #[test]
+    fn open_exclusive_closes_siblings() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let india = TreeItem::new_leaf("India").identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, delta]);
+        state.open(vec![india]);
+
+        assert!(state.open_exclusive(&items, vec![items[0].identifier()]));
+        // India is not part of the items and therefore no sibling
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![items[0].identifier()], vec![india]])
+        );
+
+        assert!(state.open_exclusive(&items, vec![bravo]));
+        assert!(state.open_exclusive(&items, vec![bravo, delta]));
+        assert!(!state.open_exclusive(&items, vec![bravo, delta]));
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![bravo], vec![bravo, delta], vec![india]])
+        );
+
+        assert!(!state.open_exclusive(&items, vec![42, 1]));
+        assert!(!state.open_exclusive(&items, Vec::new()));
+    }
+
+    #[test]
+    fn scrollbar_state_follows_last_render() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        assert_eq!(state.scrollbar_state(), (0, 0));
+
+        state.open(vec![items[1].identifier()]);
+        render(&items, &mut state);
+        assert_eq!(state.scrollbar_state(), (6, 0));
+
+        state.scroll_down(2);
+        assert_eq!(state.scrollbar_state(), (6, 2));
+    }
+
+    #[test]
+    fn set_selected_round_trip() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        render(&items, &mut state);
+        state.key_down();
+        state.key_down();
+        state.key_down();
+        let persisted = state.selected().to_vec();
+
+        let mut restored = TreeState::default();
+        restored.set_selected(persisted);
+        assert_eq!(restored.selected(), state.selected());
+        assert!(restored.ensure_selected_in_view_on_next_render);
+    }
+
+    #[test]
+    fn set_opened_ignores_unknown_identifiers() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.set_opened(HashSet::from([vec![bravo], vec![42], vec![bravo, 42]]));
+        assert_eq!(state.opened().len(), 3);
+        assert_eq!(state.flatten(&items).len(), 6);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, items[1].children()[1].identifier()]);
+        state.select(vec![bravo, items[1].children()[2].identifier()]);
+        render(&items, &mut state);
+        state.scroll_down(2);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let restored = serde_json::from_str::<TreeState>(&json).unwrap();
+        assert_eq!(restored.opened(), state.opened());
+        assert_eq!(restored.selected(), state.selected());
+        assert_eq!(restored.offset(), 2);
+        assert!(restored.last_identifiers.is_empty());
+    }
+
+    #[test]
+    fn select_first_without_visible_items_clears_selection() {
+        let mut state = TreeState::default();
+        state.select(vec![42]);
+        render(&[], &mut state);
+
+        assert!(!state.select_first());
+        assert!(state.selected().is_empty());
+        state.select(vec![42]);
+        assert!(!state.select_last());
+        assert!(state.selected().is_empty());
+    }
+
+    #[test]
+    fn selected_item() {
+        let items = TreeItem::example();
+        let bravo = &items[1];
+        let mut state = TreeState::default();
+        assert!(state.selected_item(&items).is_none());
+
+        state.select(vec![bravo.identifier(), bravo.children()[1].identifier()]);
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Delta");
+
+        state.select(vec![bravo.identifier(), 42]);
+        assert!(state.selected_item(&items).is_none());
+    }
+
+    #[test]
+    fn select_parent() {
+        let items = TreeItem::example();
+        let bravo = &items[1];
+        let delta = bravo.children()[1].identifier();
+        let mut state = TreeState::default();
+        assert!(!state.select_parent(&items));
+
+        state.open(vec![bravo.identifier(), delta]);
+        state.select(vec![bravo.identifier(), delta]);
+        state.ensure_selected_in_view_on_next_render = false;
+        assert!(state.select_parent(&items));
+        assert_eq!(state.selected(), [bravo.identifier()]);
+        assert!(state.ensure_selected_in_view_on_next_render);
+        assert!(state.opened().contains(&vec![bravo.identifier(), delta]));
+
+        assert!(!state.select_parent(&items));
+        assert_eq!(state.selected(), [bravo.identifier()]);
+
+        state.select(vec![42, delta]);
+        assert!(!state.select_parent(&items));
+    }
+
+    #[test]
+    fn select_siblings() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let children = items[1].children();
+        let mut state = TreeState::default();
+        assert!(!state.select_next_sibling(&items));
+
+        state.open(vec![bravo]);
+        state.open(vec![bravo, children[1].identifier()]);
+        state.select(vec![bravo, children[0].identifier()]);
+        assert!(state.select_next_sibling(&items));
+        assert_eq!(state.selected(), [bravo, children[1].identifier()]);
+        assert!(state.select_next_sibling(&items));
+        assert_eq!(state.selected(), [bravo, children[2].identifier()]);
+        assert!(!state.select_next_sibling(&items));
+
+        assert!(state.select_prev_sibling(&items));
+        assert!(state.select_prev_sibling(&items));
+        assert_eq!(state.selected(), [bravo, children[0].identifier()]);
+        assert!(!state.select_prev_sibling(&items));
+
+        state.select(vec![bravo]);
+        assert!(state.select_next_sibling(&items));
+        assert_eq!(state.selected(), [items[2].identifier()]);
+    }
+
+    #[test]
+    fn select_siblings_skips_unselectable() {
+        let items = vec![
+            TreeItem::new_leaf("a"),
+            TreeItem::new_leaf("b").selectable(false),
+            TreeItem::new_leaf("c"),
+        ];
+        let mut state = TreeState::default();
+        state.select(vec![items[0].identifier()]);
+        assert!(state.select_next_sibling(&items));
+        assert_eq!(state.selected(), [items[2].identifier()]);
+        assert!(state.select_prev_sibling(&items));
+        assert_eq!(state.selected(), [items[0].identifier()]);
+    }
+
+    #[test]
+    fn key_left_closes_an_open_node() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.select(vec![bravo]);
+        render(&items, &mut state);
+
+        assert!(state.key_left());
+        assert!(state.opened().is_empty());
+        assert_eq!(state.selected(), [bravo]);
+    }
+
+    #[test]
+    fn key_left_on_a_closed_node_or_leaf_selects_the_parent() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let charlie = items[1].children()[0].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.select(vec![bravo, delta]);
+        render(&items, &mut state);
+        assert!(state.key_left());
+        assert_eq!(state.selected(), [bravo]);
+
+        // A leaf which is marked as open is still a leaf
+        state.open(vec![bravo, charlie]);
+        state.select(vec![bravo, charlie]);
+        render(&items, &mut state);
+        assert!(state.key_left());
+        assert_eq!(state.selected(), [bravo]);
+        assert!(state.opened().contains(&vec![bravo]));
+    }
+
+    #[test]
+    fn key_right_opens_a_closed_node() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.select(vec![bravo]);
+        render(&items, &mut state);
+
+        assert!(state.key_right());
+        assert!(state.opened().contains(&vec![bravo]));
+        assert_eq!(state.selected(), [bravo]);
+    }
+
+    #[test]
+    fn key_right_on_an_open_node_selects_the_first_child() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let charlie = items[1].children()[0].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.select(vec![bravo]);
+        render(&items, &mut state);
+
+        assert!(state.key_right());
+        assert_eq!(state.selected(), [bravo, charlie]);
+
+        // Nodes without children are opened to request their children
+        render(&items, &mut state);
+        assert!(state.key_right());
+        assert_eq!(state.selected(), [bravo, charlie]);
+        assert!(state.is_open(&[bravo, charlie]));
+        assert!(!state.key_right());
+    }
+
+    #[test]
+    fn search_wraps_around() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(vec![bravo, items[1].children()[1].identifier()]);
+
+        assert!(state.search_forward(&items, "f"));
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Foxtrot");
+        assert!(state.search_forward(&items, "H"));
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Hotel");
+        assert!(state.search_forward(&items, "a"));
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Alfa");
+
+        assert!(state.search_backward(&items, "E"));
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Echo");
+        assert!(state.search_backward(&items, "hOt"));
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Hotel");
+
+        assert!(!state.search_forward(&items, "x"));
+        assert!(!state.search_forward(&items, ""));
+        assert_eq!(state.selected_item(&items).unwrap().content(), &"Hotel");
+    }
+
+    #[test]
+    fn search_only_finds_visible_items() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        assert!(!state.search_forward(&items, "Echo"));
+        assert!(state.selected().is_empty());
+    }
+
+    #[test]
+    fn scroll_to_opens_ancestors_and_reveals() {
+        let mut items = (0..15)
+            .map(|index| TreeItem::new_leaf(index.to_string()))
+            .collect::<Vec<_>>();
+        let child = TreeItem::new_leaf("C".to_owned());
+        let child_identifier = child.identifier();
+        items.push(TreeItem::new("P".to_owned(), vec![child]).unwrap());
+        let parent_identifier = items[15].identifier();
+        let render = |items: &[TreeItem<String>], state: &mut TreeState| {
+            let tree = Tree::new(items).unwrap();
+            let area = Rect::new(0, 0, 20, 10);
+            StatefulWidget::render(tree, area, &mut Buffer::empty(area), state);
+        };
+
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+        state.scroll_to(vec![parent_identifier, 42]);
+        render(&items, &mut state);
+        assert_eq!(state.offset(), 0);
+        assert!(state.opened().is_empty());
+
+        state.scroll_to(vec![parent_identifier, child_identifier]);
+        render(&items, &mut state);
+        assert_eq!(state.opened(), &HashSet::from([vec![parent_identifier]]));
+        assert_eq!(state.offset(), 7);
+        assert!(state.selected().is_empty());
+    }
+
+    #[test]
+    fn navigation_reports_whether_anything_changed() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        assert!(!state.toggle_selected());
+        assert!(state.select_first());
+        assert!(!state.key_up());
+        assert!(state.key_down());
+        assert!(state.key_right());
+        assert!(state.is_open(&[bravo]));
+
+        render(&items, &mut state);
+        assert!(!state.open(vec![bravo]));
+        assert!(state.close(&[bravo]));
+        assert!(!state.close(&[bravo]));
+        assert!(state.toggle_selected());
+        assert!(state.key_left());
+        assert!(!state.is_open(&[bravo]));
+        assert!(state.key_left());
+        assert!(state.selected().is_empty());
+        assert!(!state.key_left());
+        render(&items, &mut state);
+        state.select_last();
+        assert!(!state.key_down());
+    }
+
+    #[test]
+    fn toggle_selected_recursive() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        assert!(!state.toggle_selected_recursive(&items));
+
+        state.select(vec![bravo]);
+        state.ensure_selected_in_view_on_next_render = false;
+        assert!(state.toggle_selected_recursive(&items));
+        assert!(state.ensure_selected_in_view_on_next_render);
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![bravo], vec![bravo, delta]])
+        );
+
+        state.close(&[bravo, delta]);
+        assert!(state.toggle_selected_recursive(&items));
+        assert!(state.opened().is_empty());
+
+        state.select(vec![bravo, delta]);
+        assert!(state.toggle_selected_recursive(&items));
+        assert_eq!(state.opened(), &HashSet::from([vec![bravo, delta]]));
+
+        state.select(vec![items[0].identifier()]);
+        assert!(!state.toggle_selected_recursive(&items));
+    }
+
+    #[test]
+    fn key_up_and_down_stop_at_the_ends() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        state.select_last();
+        assert!(!state.key_down());
+        assert_eq!(state.selected(), [items[2].identifier()]);
+        state.select_first();
+        assert!(!state.key_up());
+        assert_eq!(state.selected(), [items[0].identifier()]);
+    }
+
+    #[test]
+    fn key_up_and_down_wrap_around() {
+        let items = vec![
+            TreeItem::new_leaf("Alfa").selectable(false),
+            TreeItem::new_leaf("Bravo"),
+            TreeItem::new_leaf("Charlie"),
+            TreeItem::new_leaf("Delta").selectable(false),
+        ];
+        let mut state = TreeState::default();
+        let tree = Tree::new(&items).unwrap().wrap_navigation(true);
+        let area = Rect::new(0, 0, 20, 10);
+        StatefulWidget::render(tree, area, &mut Buffer::empty(area), &mut state);
+
+        state.select_last();
+        state.ensure_selected_in_view_on_next_render = false;
+        assert!(state.key_down());
+        assert_eq!(state.selected(), [items[1].identifier()]);
+        assert!(state.ensure_selected_in_view_on_next_render);
+        assert!(state.key_up());
+        assert_eq!(state.selected(), [items[2].identifier()]);
+    }
+
+    #[test]
+    fn open_on_select() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        let render = |state: &mut TreeState| {
+            let tree = Tree::new(&items).unwrap().open_on_select(true);
+            let area = Rect::new(0, 0, 20, 10);
+            StatefulWidget::render(tree, area, &mut Buffer::empty(area), state);
+        };
+        render(&mut state);
+
+        assert!(state.key_down());
+        assert!(state.opened().is_empty());
+        assert!(state.key_down());
+        assert_eq!(state.selected(), [bravo]);
+        assert!(state.opened().contains(&vec![bravo]));
+
+        render(&mut state);
+        assert!(state.key_down());
+        assert_eq!(
+            state.selected(),
+            [bravo, items[1].children()[0].identifier()]
+        );
+
+        // Closing does not get undone by the selection
+        assert!(state.key_up());
+        render(&mut state);
+        assert!(state.key_left());
+        assert!(state.opened().is_empty());
+        assert_eq!(state.selected(), [bravo]);
+    }
+
+    #[test]
+    fn set_checked_propagates() {
+        let items = TreeItem::example();
+        let bravo = &items[1];
+        let delta = &bravo.children()[1];
+        let path = |parts: &[&TreeItem<&str>]| {
+            parts
+                .iter()
+                .map(|item| item.identifier())
+                .collect::<Vec<_>>()
+        };
+        let echo = path(&[bravo, delta, &delta.children()[0]]);
+        let foxtrot = path(&[bravo, delta, &delta.children()[1]]);
+        let mut state = TreeState::default();
+        assert!(!state.set_checked(&items, &[42], true));
+
+        // Checking a parent checks everything below
+        assert!(state.set_checked(&items, &path(&[bravo, delta]), true));
+        assert_eq!(
+            state.checked_paths(),
+            &HashSet::from([path(&[bravo, delta]), echo.clone(), foxtrot.clone()])
+        );
+        assert_eq!(
+            state.partially_checked(&items),
+            HashSet::from([path(&[bravo])])
+        );
+
+        // Unchecking one child makes the parent partial
+        assert!(state.set_checked(&items, &echo, false));
+        assert_eq!(state.checked_paths(), &HashSet::from([foxtrot]));
+        assert_eq!(
+            state.partially_checked(&items),
+            HashSet::from([path(&[bravo]), path(&[bravo, delta])])
+        );
+
+        // Checking all children checks the parents
+        assert!(state.set_checked(&items, &echo, true));
+        assert!(state.set_checked(&items, &path(&[bravo, &bravo.children()[0]]), true));
+        assert!(state.set_checked(&items, &path(&[bravo, &bravo.children()[2]]), true));
+        assert!(state.checked_paths().contains(&path(&[bravo])));
+        assert!(!state.set_checked(&items, &echo, true));
+
+        assert!(state.set_checked(&items, &path(&[bravo]), false));
+        assert!(state.checked_paths().is_empty());
+    }
+
+    #[test]
+    fn open_limit_evicts_least_recently_opened() {
+        let mut state = TreeState::default();
+        state.open(vec![1]);
+        state.set_open_limit(Some(2));
+        state.open(vec![2]);
+        assert_eq!(state.opened(), &HashSet::from([vec![1], vec![2]]));
+        state.open(vec![3]);
+        assert_eq!(state.opened(), &HashSet::from([vec![2], vec![3]]));
+
+        // Closed nodes do not count towards the limit
+        state.close(&[3]);
+        state.open(vec![4]);
+        state.open(vec![5]);
+        assert_eq!(state.opened(), &HashSet::from([vec![4], vec![5]]));
+
+        state.set_open_limit(Some(1));
+        assert_eq!(state.opened(), &HashSet::from([vec![5]]));
+
+        state.set_open_limit(None);
+        state.open(vec![6]);
+        state.open(vec![7]);
+        assert_eq!(state.opened().len(), 3);
+    }
+
+    #[test]
+    fn open_limit_keeps_ancestors_of_the_opened_node() {
+        let mut state = TreeState::default();
+        state.set_open_limit(Some(2));
+        state.open(vec![1]);
+        state.open(vec![2]);
+        state.open(vec![2, 3]);
+        assert_eq!(state.opened(), &HashSet::from([vec![2], vec![2, 3]]));
+
+        state.open(vec![2, 3, 4]);
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![2], vec![2, 3], vec![2, 3, 4]])
+        );
+
+        state.open(vec![5]);
+        assert_eq!(state.opened(), &HashSet::from([vec![2, 3, 4], vec![5]]));
+    }
+
+    #[test]
+    fn open_limit_applies_to_every_open_node() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+
+        // Already open nodes are closed from the deepest
+        let mut state = TreeState::default();
+        state.set_opened(HashSet::from([vec![1], vec![2], vec![2, 3]]));
+        state.set_open_limit(Some(2));
+        assert_eq!(state.opened(), &HashSet::from([vec![1], vec![2]]));
+
+        state.set_opened(HashSet::from([vec![7], vec![8], vec![8, 9]]));
+        state.set_open_limit(Some(1));
+        assert_eq!(state.opened(), &HashSet::from([vec![8]]));
+        state.set_opened(HashSet::from([vec![1], vec![2]]));
+        assert_eq!(state.opened().len(), 1);
+
+        // Nodes opened in bulk are tracked too
+        let mut state = TreeState::default();
+        state.set_open_limit(Some(1));
+        assert_eq!(state.open_all_under(&items), 2);
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![bravo], vec![bravo, delta]])
+        );
+        state.open(vec![42]);
+        assert_eq!(state.opened(), &HashSet::from([vec![42]]));
+
+        render(&items, &mut state);
+        state.select(vec![bravo, delta]);
+        assert!(state.toggle_selected_recursive(&items));
+        assert_eq!(state.opened(), &HashSet::from([vec![bravo, delta]]));
+        assert!(state.collapse_all_except_selected(&items));
+        assert_eq!(state.opened(), &HashSet::from([vec![bravo]]));
+    }
+
+    #[test]
+    fn take_pending_open_for_lazy_children() {
+        let mut items = vec![
+            TreeItem::new_leaf("folder"),
+            TreeItem::new("loaded", vec![TreeItem::new_leaf("child")]).unwrap(),
+        ];
+        let folder = items[0].identifier();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+        assert!(state.take_pending_open().is_none());
+
+        state.open(vec![items[1].identifier()]);
+        assert!(state.take_pending_open().is_none());
+
+        state.select(vec![folder]);
+        assert!(state.toggle_selected());
+        assert_eq!(state.take_pending_open(), Some(vec![folder]));
+        assert!(state.take_pending_open().is_none());
+
+        items[0] = TreeItem::new("folder", vec![TreeItem::new_leaf("lazy")]).unwrap();
+        render(&items, &mut state);
+        assert_eq!(state.flatten(&items).len(), 4);
+    }
+
+    #[test]
+    fn take_pending_open_from_key_right_and_toggle_at() {
+        let items = vec![TreeItem::new_leaf("folder"), TreeItem::new_leaf("other")];
+        let folder = items[0].identifier();
+        let other = items[1].identifier();
+        let mut state = TreeState::default();
+        render(&items, &mut state);
+
+        state.select(vec![folder]);
+        assert!(state.key_right());
+        assert_eq!(state.take_pending_open(), Some(vec![folder]));
+
+        assert_eq!(state.toggle_at(Position::new(5, 1)), ToggleAction::Selected);
+        assert_eq!(state.selected(), [other]);
+        assert_eq!(state.take_pending_open(), Some(vec![other]));
+    }
+
+    #[test]
+    fn prune_forgets_removed_nodes() {
+        let mut items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = vec![bravo, items[1].children()[1].identifier()];
+        let echo = vec![
+            bravo,
+            delta[1],
+            items[1].children()[1].children()[0].identifier(),
+        ];
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.open(delta);
+        state.select(echo.clone());
+        state.toggle_selected_multi();
+        state.select(vec![items[0].identifier()]);
+        state.toggle_selected_multi();
+        state.select(echo);
+        assert!(!state.prune(&items));
+
+        // Rebuild without the Delta subtree
+        items[1] = TreeItem::new(
+            "Bravo",
+            vec![TreeItem::new_leaf("Charlie"), TreeItem::new_leaf("Golf")],
+        )
+        .unwrap();
+        assert!(state.prune(&items));
+        assert_eq!(state.opened(), &HashSet::from([vec![bravo]]));
+        assert_eq!(
+            state.selected_multi(),
+            &HashSet::from([vec![items[0].identifier()]])
+        );
+        assert!(state.selected().is_empty());
+        assert!(!state.prune(&items));
+    }
+
+    #[test]
+    fn collapse_all_except_selected_keeps_ancestors() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.open(vec![items[2].identifier()]);
+        let delta = &items[1].children()[1];
+        state.open(vec![items[1].identifier(), delta.identifier()]);
+        state.select(vec![
+            items[1].identifier(),
+            delta.identifier(),
+            delta.children()[0].identifier(),
+        ]);
+
+        assert!(state.collapse_all_except_selected(&items));
+        let expected = HashSet::from([
+            vec![items[1].identifier()],
+            vec![items[1].identifier(), delta.identifier()],
+        ]);
+        assert_eq!(state.opened(), &expected);
+        assert!(!state.collapse_all_except_selected(&items));
+
+        state.select(Vec::new());
+        assert!(state.collapse_all_except_selected(&items));
+        assert!(state.opened().is_empty());
+    }
+
+    #[test]
+    fn visible_count_and_identifiers() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        assert_eq!(state.visible_count(&items), 3);
+        assert_eq!(
+            state.visible_identifiers(&items),
+            [
+                vec![items[0].identifier()],
+                vec![bravo],
+                vec![items[2].identifier()]
+            ]
+        );
+
+        // Not visible while the parent is closed
+        state.open(vec![bravo, delta]);
+        assert_eq!(state.visible_count(&items), 3);
+
+        state.open(vec![bravo]);
+        assert_eq!(state.visible_count(&items), 8);
+        let identifiers = state.visible_identifiers(&items);
+        assert_eq!(identifiers[3], [bravo, delta]);
+        assert_eq!(
+            identifiers,
+            state
+                .flatten(&items)
+                .into_iter()
+                .map(|flattened| flattened.identifier)
+                .collect::<Vec<_>>()
+        );
+
+        state.close(&[bravo, delta]);
+        assert_eq!(state.visible_count(&items), 6);
+    }
+
+    #[test]
+    fn reset_returns_to_default() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        render(&items, &mut state);
+        state.key_down();
+        state.key_down();
+        state.toggle_selected_multi();
+        state.scroll_down(1);
+        state.scroll_to(vec![items[2].identifier()]);
+        state.set_open_limit(Some(3));
+        state.open(vec![items[2].identifier()]);
+
+        state.reset();
+        assert_eq!(state.offset(), 0);
+        assert!(state.opened().is_empty());
+        assert!(state.selected().is_empty());
+        assert!(state.selected_multi().is_empty());
+        assert!(state.take_pending_open().is_none());
+        assert!(state.scroll_to_on_next_render.is_none());
+        assert!(state.open_limit.is_none());
+        assert!(state.open_order.is_empty());
+        assert!(!state.is_revealing());
+        assert!(state.last_identifiers.is_empty());
+        assert!(state.last_indices.is_empty());
+        assert!(state.last_rendered.is_empty());
+        assert_eq!(format!("{state:?}"), format!("{:?}", TreeState::default()));
+    }
+
+    #[test]
+    fn closing_forgets_reveal_progress_and_open_order() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        state.set_open_limit(Some(5));
+
+        state.open(vec![bravo]);
+        state.open(vec![bravo, delta]);
+        assert!(state.close_all());
+        assert!(state.open_order.is_empty());
+        assert!(!state.is_revealing());
+
+        state.open(vec![bravo]);
+        state.open(vec![bravo, delta]);
+        assert_eq!(state.close_all_under(&items), 2);
+        assert!(state.open_order.is_empty());
+        assert!(!state.is_revealing());
+
+        state.open(vec![bravo]);
+        state.select(vec![bravo]);
+        render(&items, &mut state);
+        assert!(state.key_left());
+        assert!(state.open_order.is_empty());
+        assert!(!state.is_revealing());
+    }
+
+    #[test]
+    fn select_visible_index_in_clamps() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+
+        assert!(state.select_visible_index_in(&items, 2));
+        assert_eq!(
+            state.selected(),
+            [items[1].identifier(), items[1].children()[0].identifier()]
+        );
+        assert!(!state.select_visible_index_in(&items, 2));
+
+        assert!(state.select_visible_index_in(&items, 42));
+        assert_eq!(state.selected(), [items[2].identifier()]);
+
+        assert!(!state.select_visible_index_in::<&str>(&[], 0));
+        assert_eq!(state.selected(), [items[2].identifier()]);
+    }
+
+    #[test]
+    fn selected_position_of_example() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let mut state = TreeState::default();
+        assert_eq!(state.selected_position(&items), None);
+
+        state.select(vec![bravo, delta]);
+        assert_eq!(state.selected_position(&items), Some((1, 1, 3)));
+
+        state.select(vec![items[2].identifier()]);
+        assert_eq!(state.selected_position(&items), Some((0, 2, 3)));
+
+        state.select(vec![delta]);
+        assert_eq!(state.selected_position(&items), None);
+    }
+
+    #[test]
+    fn selected_breadcrumb_joins_labels() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let delta = items[1].children()[1].identifier();
+        let echo = items[1].children()[1].children()[0].identifier();
+        let mut state = TreeState::default();
+        assert_eq!(state.selected_breadcrumb(&items, " / "), None);
+
+        state.select(vec![bravo, delta, echo]);
+        assert_eq!(
+            state.selected_breadcrumb(&items, " / ").as_deref(),
+            Some("Bravo / Delta / Echo")
+        );
+
+        state.select(vec![bravo, echo]);
+        assert_eq!(state.selected_breadcrumb(&items, " / "), None);
+    }
+
+    #[test]
+    fn open_and_close_level() {
+        let items = vec![
+            TreeItem::new_leaf("Alfa"),
+            TreeItem::new("Bravo", vec![TreeItem::new_leaf("Charlie")]).unwrap(),
+            TreeItem::new(
+                "Delta",
+                vec![TreeItem::new("Echo", vec![TreeItem::new_leaf("Foxtrot")]).unwrap()],
+            )
+            .unwrap(),
+        ];
+        let mut state = TreeState::default();
+        assert_eq!(state.open_level(&items, 0), 2);
+        assert_eq!(
+            state.opened(),
+            &HashSet::from([vec![items[1].identifier()], vec![items[2].identifier()]])
+        );
+        assert_eq!(state.open_level(&items, 0), 0);
+
+        assert_eq!(state.open_level(&items, 1), 1);
+        assert_eq!(state.visible_count(&items), 6);
+
+        assert_eq!(state.close_level(&items, 0), 2);
+        assert_eq!(state.opened().len(), 1);
+        assert_eq!(state.open_level(&items, 5), 0);
+    }
+
+    #[test]
+    fn ensure_selected_in_view_is_consumed_by_render() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        let tree = Tree::new(&items).unwrap();
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buffer = Buffer::empty(area);
+
+        state.selected = vec![items[2].identifier()];
+        StatefulWidget::render(tree.clone(), area, &mut buffer, &mut state);
+        assert_eq!(state.offset(), 0);
+
+        state.ensure_selected_in_view();
+        StatefulWidget::render(tree.clone(), area, &mut buffer, &mut state);
+        assert_eq!(state.offset(), 2);
+
+        state.scroll_up(2);
+        StatefulWidget::render(tree, area, &mut buffer, &mut state);
+        assert_eq!(state.offset(), 0);
+    }
+
+    #[test]
+    fn set_offset_is_clamped_on_render() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        state.open(vec![items[1].identifier()]);
+        state.select(vec![items[0].identifier()]);
+        let tree = Tree::new(&items).unwrap();
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buffer = Buffer::empty(area);
+        StatefulWidget::render(tree.clone(), area, &mut buffer, &mut state);
+
+        state.set_offset(2);
+        StatefulWidget::render(tree.clone(), area, &mut buffer, &mut state);
+        assert_eq!(state.offset(), 2);
+        assert_eq!(state.selected(), [items[0].identifier()]);
+
+        state.set_offset(42);
+        StatefulWidget::render(tree, area, &mut buffer, &mut state);
+        assert_eq!(state.offset(), 3);
+    }
+
+    #[test]
+    fn scroll_progress() {
+        let items = TreeItem::example();
+        let mut state = TreeState::default();
+        let tree = Tree::new(&items).unwrap();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 4));
+        StatefulWidget::render(tree.clone(), buffer.area, &mut buffer, &mut state);
+        assert!(state.scroll_progress().abs() < f32::EPSILON);
+
+        state.open(vec![items[1].identifier()]);
+        StatefulWidget::render(tree.clone(), buffer.area, &mut buffer, &mut state);
+        assert!(state.scroll_progress().abs() < f32::EPSILON);
+
+        state.scroll_down(1);
+        StatefulWidget::render(tree.clone(), buffer.area, &mut buffer, &mut state);
+        assert!((state.scroll_progress() - 0.5).abs() < f32::EPSILON);
+
+        state.scroll_down(1);
+        StatefulWidget::render(tree, buffer.area, &mut buffer, &mut state);
+        assert!((state.scroll_progress() - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn scroll_by_clamps_at_last_page() {
+        let items = TreeItem::example();
+        let bravo = items[1].identifier();
+        let mut state = TreeState::default();
+        state.open(vec![bravo]);
+        state.select(vec![items[0].identifier()]);
+        let tree = Tree::new(&items).unwrap();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 4));
+        StatefulWidget::render(tree.clone(), buffer.area, &mut buffer, &mut state);
+
+        assert!(state.scroll_by(1));
+        assert_eq!(state.offset(), 1);
+
+        // 6 visible items, the last page of 4 starts at Charlie
+        assert!(state.scroll_by(10));
+        assert_eq!(state.offset(), 2);
+        assert!(!state.scroll_by(1));
+        StatefulWidget::render(tree, buffer.area, &mut buffer, &mut state);
+        assert_eq!(
+            state.rendered_at(Position::new(0, 0)),
+            Some([bravo, items[1].children()[0].identifier()].as_slice())
+        );
+        assert_eq!(state.selected(), [items[0].identifier()]);
+
+        assert!(state.scroll_by(-5));
+        assert_eq!(state.offset(), 0);
+        assert!(!state.scroll_by(-1));
     }
 }